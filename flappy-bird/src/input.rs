@@ -6,7 +6,15 @@ use ggez_extras::input;
 pub enum Button {
     Enter,
     Quit,
+    Debug,
+    Mute,
+    Pause,
     Space,
+    Up,
+    Down,
+    Left,
+    Right,
+    Settings,
 }
 
 pub type Binding = input::InputBinding<Button>;
@@ -16,6 +24,14 @@ pub type State = input::InputState<Button>;
 pub fn create_input_binding() -> input::InputBinding<Button> {
     input::InputBinding::new()
         .bind_key_to_button(KeyCode::Space, Button::Space)
+        .bind_key_to_button(KeyCode::Up, Button::Up)
+        .bind_key_to_button(KeyCode::Down, Button::Down)
+        .bind_key_to_button(KeyCode::Left, Button::Left)
+        .bind_key_to_button(KeyCode::Right, Button::Right)
         .bind_key_to_button(KeyCode::Return, Button::Enter)
         .bind_key_to_button(KeyCode::Escape, Button::Quit)
+        .bind_key_to_button(KeyCode::F2, Button::Settings)
+        .bind_key_to_button(KeyCode::F3, Button::Debug)
+        .bind_key_to_button(KeyCode::M, Button::Mute)
+        .bind_key_to_button(KeyCode::P, Button::Pause)
 }
@@ -1,13 +1,18 @@
-use std::env;
 use std::path;
 
-use ggez::{
-    audio, audio::SoundSource, conf, event, graphics, mint, timer, Context, ContextBuilder,
-    GameResult,
+use ggez::{conf, event, graphics, timer, Context, ContextBuilder, GameError, GameResult};
+use ggez_extras::{
+    assets::{AssetWatcher, Assets},
+    audio::{AudioManager, AudioSystem, Bus, SoundQueue},
+    camera::{Camera, CameraSystem},
+    logging,
+    particles::ParticleSystem,
+    render::{DebugOverlay, RenderLayers, RenderSystem, ScaledCanvas, ScalingMode},
+    resources,
+    settings::Settings,
 };
-use ggez_extras::logging;
-use log::info;
-use specs::{Builder, World, WorldExt};
+use log::{debug, info, warn};
+use specs::{Builder, RunNow, World, WorldExt};
 
 mod components;
 mod input;
@@ -17,7 +22,19 @@ mod types;
 
 use types::*;
 
+// With the `embed-resources` feature, the build script packs the resources
+// into the binary.
+#[cfg(feature = "embed-resources")]
+const PACKED_RESOURCES: Option<&[u8]> =
+    Some(include_bytes!(concat!(env!("OUT_DIR"), "/resources.zip")));
+#[cfg(not(feature = "embed-resources"))]
+const PACKED_RESOURCES: Option<&[u8]> = None;
+
+const GAME_ID: &str = "flappy-bird";
+const AUTHOR: &str = "Tommy Maintz";
+
 const DESIRED_UPS: u32 = 60;
+// The window size until the player picks another one in the settings.
 const WINDOW_WIDTH: f32 = 1280.0;
 const WINDOW_HEIGHT: f32 = 780.0;
 
@@ -36,109 +53,192 @@ const PIPE_HEIGHT: f32 = 430.0;
 const BIRD_WIDTH: f32 = 38.0;
 const BIRD_HEIGHT: f32 = 24.0;
 
+const ASSETS_PATH: &str = "/assets.json";
+
+const LAYER_BACKGROUND: &str = "background";
+const LAYER_PIPES: &str = "pipes";
+const LAYER_GROUND: &str = "ground";
+const LAYER_PLAYER: &str = "player";
+const LAYER_UI: &str = "ui";
+
+// Keeps text readable over the scrolling background.
+const TEXT_SHADOW: TextEffect = TextEffect::Shadow {
+    offset: ggez::mint::Vector2 { x: 2.0, y: 2.0 },
+    color: graphics::Color {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 0.6,
+    },
+};
+
 pub struct Game {
     world: World,
     scenes: scenes::SceneStack,
     input_binding: input::Binding,
+    /// Owned by the game rather than the world, as the game drives it.
+    render_system: RenderSystem,
+    /// Reloads changed assets in debug builds.
+    watcher: Option<AssetWatcher>,
 }
 
 impl Game {
-    fn new(ctx: &mut Context, _resource_path: &path::Path) -> GameResult<Game> {
+    fn new(
+        ctx: &mut Context,
+        resource_path: &path::Path,
+        settings: Settings,
+        audio: bool,
+    ) -> GameResult<Game> {
         let mut world = World::new();
         components::register(&mut world);
 
-        let render_system = systems::RenderSystem::new(&mut world);
-
-        let images = Images {
-            background: graphics::Image::new(ctx, "/images/background.png")?,
-            bird: graphics::Image::new(ctx, "/images/bird.png")?,
-            ground: graphics::Image::new(ctx, "/images/ground.png")?,
-            pipe: graphics::Image::new(ctx, "/images/pipe.png")?,
-        };
-
-        let mut fonts = Fonts::new();
-        fonts.insert(
-            FontType::Retro,
-            graphics::Font::new(ctx, "/fonts/retro.ttf")?,
-        );
-        fonts.insert(
-            FontType::Flappy,
-            graphics::Font::new(ctx, "/fonts/flappy.ttf")?,
-        );
-
         // We use a fixed DeltaTime for all our systems
         world.insert(DeltaTime {
             delta: 1.0 / DESIRED_UPS as f32,
         });
-        world.insert(fonts);
-        world.insert(images.clone());
-        world.insert(render_system);
+        let render_system = RenderSystem::new(&mut world)
+            .with_show_fps(settings.show_fps)
+            .with_canvas(ScaledCanvas::new(
+                ctx,
+                VIRTUAL_WIDTH as u16,
+                VIRTUAL_HEIGHT as u16,
+                ScalingMode::Letterbox,
+            )?);
+
+        world.insert(
+            DebugOverlay::new().with_vectors(|velocity: &components::Velocity| {
+                ggez::mint::Vector2 {
+                    x: velocity.x * 5.0,
+                    y: velocity.y * 5.0,
+                }
+            }),
+        );
         world.insert(input::State::new());
-        world.insert(Sounds {
-            explosion: audio::Source::new(ctx, "/sounds/explosion.wav")?,
-            hurt: audio::Source::new(ctx, "/sounds/hurt.wav")?,
-            jump: audio::Source::new(ctx, "/sounds/jump.wav")?,
-            score: audio::Source::new(ctx, "/sounds/score.wav")?,
-        });
+        world.insert(Camera::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT));
+        // Pipes slide out from behind the ground, and the background only
+        // moves with half of the camera shake.
+        world.insert(
+            RenderLayers::new()
+                .world(LAYER_BACKGROUND, 0.5)
+                .world(LAYER_PIPES, 1.0)
+                .world(LAYER_GROUND, 1.0)
+                .world(LAYER_PLAYER, 1.0)
+                .screen(LAYER_UI),
+        );
+
+        // The loading scene fills the assets and then calls `setup_assets`.
+        world.insert(AudioManager::new(audio).with_settings(settings.audio.clone()));
+        world.insert(settings);
+        world.insert(Assets::new());
+        world.insert(SoundQueue::new());
+
+        let scene_graph = scenes::create_scene_graph();
+        debug!("Scene graph:\n{}", scene_graph.to_dot());
 
-        world
-            .create_entity()
-            .with(components::Render { visible: true })
-            .with(components::Image {
-                image: images.background,
-            })
-            .with(components::Position {
-                x: 0.0,
-                y: 0.0,
-                z: 0,
-            })
-            .with(components::Size {
-                w: VIRTUAL_WIDTH,
-                h: VIRTUAL_HEIGHT,
-            })
-            .with(components::Parallax {
-                speed: BACKGROUND_SCROLL_SPEED,
-                looping_point: BACKGROUND_LOOPING_POINT,
-            })
-            .build();
-
-        let ground_img = graphics::Image::new(ctx, "/images/ground.png")?;
-        let ground_dims = ground_img.dimensions();
-        world
-            .create_entity()
-            .with(components::Render { visible: true })
-            .with(components::Position {
-                x: 0.0,
-                y: VIRTUAL_HEIGHT - ground_dims.h,
-                z: 0,
-            })
-            .with(components::Image {
-                image: images.ground,
-            })
-            .with(components::Size {
-                w: VIRTUAL_WIDTH,
-                h: VIRTUAL_HEIGHT,
-            })
-            .with(components::Parallax {
-                speed: GROUND_SCROLL_SPEED,
-                looping_point: BACKGROUND_LOOPING_POINT,
-            })
-            .build();
-
-        let mut scenestack = scenes::SceneStack::new(ctx);
-        let title_scene = Box::new(scenes::TitleScene::new());
-        scenestack.push(title_scene, &mut world);
+        let mut scenestack = scenes::SceneStack::new(ctx).with_graph(scene_graph);
+        scenestack.push_state("loading", &mut world, ctx);
 
         Ok(Self {
             world,
             scenes: scenestack,
             input_binding: input::create_input_binding(),
+            render_system,
+            watcher: if cfg!(debug_assertions) {
+                Some(AssetWatcher::new(resource_path))
+            } else {
+                None
+            },
         })
     }
+
+    /// Picks up the assets the watcher reloaded for the entities that are
+    /// created from now on.
+    fn reload_assets(&mut self, ctx: &mut Context) -> GameResult {
+        let watcher = match &mut self.watcher {
+            Some(watcher) => watcher,
+            None => return Ok(()),
+        };
+        // Until the loading scene is done, there is nothing to update.
+        if watcher.update(ctx, &self.world) && self.world.has_value::<Images>() {
+            let assets = self.world.read_resource::<Assets>();
+            *self.world.write_resource::<Images>() = Images::load(&assets)?;
+            *self.world.write_resource::<Fonts>() = load_fonts(&assets)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets up the resources and entities that use the assets, once the
+/// loading scene loaded them.
+fn setup_assets(world: &mut World) -> GameResult {
+    let (images, fonts, sounds) = {
+        let assets = world.read_resource::<Assets>();
+        let sounds = Sounds {
+            explosion: assets.sound("explosion")?,
+            hurt: assets.sound("hurt")?,
+            jump: assets.sound("jump")?,
+            score: assets.sound("score")?,
+            music: assets.sound("music")?,
+        };
+        (Images::load(&assets)?, load_fonts(&assets)?, sounds)
+    };
+
+    world.write_resource::<DebugOverlay>().font = fonts[&FontType::Retro];
+    world.insert(fonts);
+    world.insert(images.clone());
+    world.insert(sounds);
+
+    world
+        .create_entity()
+        .with(components::Render {
+            visible: true,
+            layer: LAYER_BACKGROUND,
+        })
+        .with(components::Image {
+            image: images.background,
+        })
+        .with(components::Position { x: 0.0, y: 0.0 })
+        .with(components::Size {
+            w: VIRTUAL_WIDTH,
+            h: VIRTUAL_HEIGHT,
+        })
+        .with(components::Parallax {
+            speed: BACKGROUND_SCROLL_SPEED,
+            looping_point: BACKGROUND_LOOPING_POINT,
+        })
+        .build();
+
+    let ground_dims = images.ground.dimensions();
+    world
+        .create_entity()
+        .with(components::Render {
+            visible: true,
+            layer: LAYER_GROUND,
+        })
+        .with(components::Position {
+            x: 0.0,
+            y: VIRTUAL_HEIGHT - ground_dims.h,
+        })
+        .with(components::Image {
+            image: images.ground,
+        })
+        .with(components::Size {
+            w: VIRTUAL_WIDTH,
+            h: VIRTUAL_HEIGHT,
+        })
+        .with(components::Parallax {
+            speed: GROUND_SCROLL_SPEED,
+            looping_point: BACKGROUND_LOOPING_POINT,
+        })
+        .build();
+
+    Ok(())
 }
 
 impl event::EventHandler for Game {
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.reload_assets(ctx)?;
+
         if self
             .world
             .read_resource::<input::State>()
@@ -146,9 +246,35 @@ impl event::EventHandler for Game {
         {
             event::quit(ctx);
         }
+        if self
+            .world
+            .read_resource::<input::State>()
+            .get_button_released(input::Button::Debug)
+        {
+            self.world.write_resource::<DebugOverlay>().toggle();
+        }
+        if self
+            .world
+            .read_resource::<input::State>()
+            .get_button_released(input::Button::Mute)
+        {
+            let mut audio = self.world.write_resource::<AudioManager>();
+            audio.toggle_muted(Bus::Master);
+            let mut settings = self.world.write_resource::<Settings>();
+            settings.audio = audio.settings().clone();
+            if let Err(e) = settings.save(GAME_ID, AUTHOR) {
+                warn!("Unable to save settings: {}", e);
+            }
+        }
 
         while timer::check_update_time(ctx, DESIRED_UPS) {
             self.scenes.update(&mut self.world, ctx);
+            if !self.scenes.current().pauses_game() {
+                CameraSystem.run_now(&self.world);
+                ParticleSystem.run_now(&self.world);
+                AudioSystem.run_now(&self.world);
+            }
+            self.world.write_resource::<DebugOverlay>().end_tick();
             self.world.write_resource::<input::State>().update();
             self.world.maintain();
         }
@@ -157,13 +283,22 @@ impl event::EventHandler for Game {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        self.world
-            .write_resource::<systems::RenderSystem>()
-            .run(ctx, &self.world)?;
+        // The fonts are there once the loading scene is done, and replaced
+        // when the watcher reloads them.
+        if let Some(fonts) = self.world.try_fetch::<Fonts>() {
+            self.render_system.set_fps_font(fonts[&FontType::Retro]);
+        }
+        let show_fps = self.world.read_resource::<Settings>().show_fps;
+        self.render_system.set_show_fps(show_fps);
+        self.render_system.run(ctx, &self.world)?;
 
         Ok(())
     }
 
+    fn resize_event(&mut self, _ctx: &mut Context, width: f32, height: f32) {
+        self.render_system.resize(width, height);
+    }
+
     fn key_down_event(
         &mut self,
         _ctx: &mut Context,
@@ -197,25 +332,30 @@ impl event::EventHandler for Game {
 fn main() -> GameResult {
     logging::setup();
 
-    let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
-        let mut path = path::PathBuf::from(manifest_dir);
-        path.push("resources");
-        path
-    } else {
-        path::PathBuf::from("./resources")
-    };
+    let resource_dir = resources::resource_dir();
     info!("Resource dir: {:?}", resource_dir);
 
-    let cb = ContextBuilder::new("pong", "Tommy Maintz")
-        .window_setup(conf::WindowSetup::default().title("Fifty Bird"))
-        .window_mode(
-            conf::WindowMode::default()
-                .dimensions(WINDOW_WIDTH, WINDOW_HEIGHT)
-                .resizable(true)
-                .borderless(false),
-        )
-        .add_resource_path(&resource_dir);
-    let (ctx, ev) = &mut cb.build().unwrap();
+    let settings = Settings::load(
+        GAME_ID,
+        AUTHOR,
+        Settings::default().with_window_size(WINDOW_WIDTH, WINDOW_HEIGHT),
+    );
+    let cb = ContextBuilder::new(GAME_ID, AUTHOR)
+        .window_setup(settings.window_setup(conf::WindowSetup::default().title("Fifty Bird")))
+        .window_mode(settings.window_mode());
+    let cb = resources::mount(cb, &resource_dir, PACKED_RESOURCES);
+    // Without an audio device the game still runs, just without sound.
+    let ((ctx, ev), audio) = &mut match cb.clone().build() {
+        Ok(context) => (context, true),
+        Err(GameError::AudioError(e)) => {
+            warn!("Unable to start with audio, continuing without: {}", e);
+            let context = cb
+                .modules(conf::ModuleConf::default().audio(false))
+                .build()?;
+            (context, false)
+        }
+        Err(e) => return Err(e),
+    };
 
     graphics::set_default_filter(ctx, graphics::FilterMode::Nearest);
     graphics::set_screen_coordinates(
@@ -228,7 +368,7 @@ fn main() -> GameResult {
         },
     )?;
 
-    let state = &mut Game::new(ctx, &resource_dir)?;
+    let state = &mut Game::new(ctx, &resource_dir, settings, *audio)?;
 
     event::run(ctx, ev, state)
 }
@@ -14,25 +14,27 @@ pub struct CountdownScene {
 impl CountdownScene {
     pub fn new(_ctx: &mut Context, world: &mut World) -> Self {
         let count = 3u8;
+        let font = world.read_resource::<Fonts>()[&FontType::Flappy];
         let text = world
             .create_entity()
-            .with(components::Render { visible: true })
+            .with(components::Render {
+                visible: true,
+                layer: LAYER_UI,
+            })
             .with(components::Text {
                 text: count.to_string(),
-                font: FontType::Flappy,
+                font,
                 font_size: 56.0,
                 color: graphics::WHITE,
                 align: Alignment::Centered,
+                effect: Some(TEXT_SHADOW),
+                ..Default::default()
             })
             .with(components::Size {
                 w: VIRTUAL_WIDTH,
                 h: f32::INFINITY,
             })
-            .with(components::Position {
-                x: 0.0,
-                y: 120.0,
-                z: 1,
-            })
+            .with(components::Position { x: 0.0, y: 120.0 })
             .build();
 
         Self {
@@ -43,7 +45,7 @@ impl CountdownScene {
     }
 }
 impl scenes::Scene for CountdownScene {
-    fn update(&mut self, world: &mut World, ctx: &mut Context) -> scenes::SceneSwitch {
+    fn update(&mut self, world: &mut World, _ctx: &mut Context) -> scenes::SceneSwitch {
         self.timer += world.read_resource::<DeltaTime>().delta;
         if self.timer > COUNTDOWN_TIME {
             self.timer = self.timer % COUNTDOWN_TIME;
@@ -54,14 +56,15 @@ impl scenes::Scene for CountdownScene {
             }
 
             if self.count == 0 {
-                return scenes::SceneSwitch::replace(scenes::PlayScene::new(ctx, world));
+                return scenes::SceneSwitch::event("done");
             }
         }
 
         scenes::SceneSwitch::None
     }
 
-    fn on_enter(&mut self, _world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        scenes::play_music(world, Some(world.read_resource::<Sounds>().music));
         Ok(Some(vec![self.text]))
     }
 
@@ -0,0 +1,177 @@
+use ggez::Context;
+use specs::World;
+
+use super::Scene;
+
+/// Creates the scene to enter when a state becomes active.
+pub type SceneFactory = Box<dyn Fn(&mut World, &mut Context) -> Box<dyn Scene>>;
+
+struct Transition {
+    from: &'static str,
+    event: &'static str,
+    to: &'static str,
+}
+
+/// A declarative description of the flow between scenes.
+///
+/// Every state is a named scene factory, and every transition maps an event
+/// emitted by the scene of one state (see `SceneSwitch::event`) to the next
+/// state. Keeping them in one place makes the flow easy to inspect, log and
+/// dump as a DOT graph.
+pub struct SceneGraph {
+    states: Vec<(&'static str, SceneFactory)>,
+    transitions: Vec<Transition>,
+}
+
+impl SceneGraph {
+    pub fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Adds a named state that creates its scene with the given factory.
+    pub fn state<F>(mut self, name: &'static str, factory: F) -> Self
+    where
+        F: Fn(&mut World, &mut Context) -> Box<dyn Scene> + 'static,
+    {
+        self.states.push((name, Box::new(factory)));
+        self
+    }
+
+    /// Adds a transition from state `from` to state `to` which fires
+    /// when the scene of `from` emits `event`.
+    pub fn transition(mut self, from: &'static str, event: &'static str, to: &'static str) -> Self {
+        self.transitions.push(Transition { from, event, to });
+        self
+    }
+
+    /// Returns the names of all states in the order they were added.
+    pub fn states(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.states.iter().map(|(name, _)| *name)
+    }
+
+    /// Returns all transitions as `(from, event, to)` triples.
+    pub fn transitions(
+        &self,
+    ) -> impl Iterator<Item = (&'static str, &'static str, &'static str)> + '_ {
+        self.transitions.iter().map(|t| (t.from, t.event, t.to))
+    }
+
+    /// Returns the state that `event` leads to when emitted from `from`.
+    pub fn target(&self, from: &str, event: &str) -> Option<&'static str> {
+        self.transitions
+            .iter()
+            .find(|t| t.from == from && t.event == event)
+            .map(|t| t.to)
+    }
+
+    pub fn has_state(&self, state: &str) -> bool {
+        self.factory(state).is_some()
+    }
+
+    fn factory(&self, state: &str) -> Option<&SceneFactory> {
+        self.states
+            .iter()
+            .find(|(name, _)| *name == state)
+            .map(|(_, factory)| factory)
+    }
+
+    /// Creates the scene for the given state, if it exists.
+    pub fn create(
+        &self,
+        state: &str,
+        world: &mut World,
+        ctx: &mut Context,
+    ) -> Option<Box<dyn Scene>> {
+        self.factory(state).map(|factory| factory(world, ctx))
+    }
+
+    /// Renders the states and transitions in the Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph scenes {\n");
+        for state in self.states() {
+            dot.push_str(&format!("    \"{}\";\n", state));
+        }
+        for (from, event, to) in self.transitions() {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                from, to, event
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{create_scene_graph, TitleScene};
+    use super::*;
+
+    #[test]
+    fn transitions_lead_to_the_next_state() {
+        let graph = create_scene_graph();
+        assert_eq!(graph.target("loading", "loaded"), Some("title"));
+        assert_eq!(graph.target("title", "start"), Some("countdown"));
+        assert_eq!(graph.target("title", "settings"), Some("settings"));
+        assert_eq!(graph.target("settings", "back"), Some("title"));
+        assert_eq!(graph.target("countdown", "done"), Some("play"));
+        assert_eq!(graph.target("play", "died"), Some("score"));
+        assert_eq!(graph.target("play", "quit"), Some("title"));
+        assert_eq!(graph.target("score", "restart"), Some("countdown"));
+    }
+
+    #[test]
+    fn unknown_transitions_have_no_target() {
+        let graph = create_scene_graph();
+        assert_eq!(graph.target("title", "died"), None);
+        assert_eq!(graph.target("missing", "start"), None);
+    }
+
+    #[test]
+    fn every_transition_connects_known_states() {
+        let graph = create_scene_graph();
+        for (from, event, to) in graph.transitions() {
+            assert!(graph.has_state(from), "{} --{}--> {}", from, event, to);
+            assert!(graph.has_state(to), "{} --{}--> {}", from, event, to);
+        }
+        assert!(!graph.has_state("missing"));
+    }
+
+    #[test]
+    fn every_state_but_loading_can_be_entered() {
+        let graph = create_scene_graph();
+        for state in graph.states().filter(|state| *state != "loading") {
+            assert!(
+                graph.transitions().any(|(_, _, to)| to == state),
+                "{} cannot be entered",
+                state
+            );
+        }
+    }
+
+    #[test]
+    fn has_state_only_knows_added_states() {
+        let graph = SceneGraph::new()
+            .state("title", |_world, _ctx| Box::new(TitleScene::new()))
+            .transition("title", "start", "play");
+        assert!(graph.has_state("title"));
+        // The transition points to a state without a factory.
+        assert_eq!(graph.target("title", "start"), Some("play"));
+        assert!(!graph.has_state("play"));
+    }
+
+    #[test]
+    fn dumps_states_and_transitions_as_dot() {
+        let graph = SceneGraph::new()
+            .state("title", |_world, _ctx| Box::new(TitleScene::new()))
+            .state("play", |_world, _ctx| Box::new(TitleScene::new()))
+            .transition("title", "start", "play");
+        assert_eq!(
+            graph.to_dot(),
+            "digraph scenes {\n    \"title\";\n    \"play\";\n    \"title\" -> \"play\" [label=\"start\"];\n}\n"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use ggez::Context;
+use ggez_extras::assets::{AssetLoader, LoadingScreen, LoadingState};
+use specs::{Entity, World};
+
+use crate::*;
+
+/// Loads the assets of the manifest while showing the progress, and sets
+/// the game up with them before the title is shown.
+pub struct LoadingScene {
+    screen: LoadingScreen,
+}
+
+impl LoadingScene {
+    pub fn new(ctx: &mut Context) -> Self {
+        Self {
+            screen: LoadingScreen::new(
+                AssetLoader::from_path(ctx, ASSETS_PATH),
+                LAYER_UI,
+                VIRTUAL_WIDTH,
+                VIRTUAL_HEIGHT,
+            ),
+        }
+    }
+}
+
+impl scenes::Scene for LoadingScene {
+    fn update(&mut self, world: &mut World, ctx: &mut Context) -> scenes::SceneSwitch {
+        if self.screen.update(world, ctx) != LoadingState::Loaded {
+            return scenes::SceneSwitch::None;
+        }
+
+        match setup_assets(world) {
+            Ok(()) => scenes::SceneSwitch::event("loaded"),
+            Err(e) => {
+                self.screen.fail(world, &e);
+                scenes::SceneSwitch::None
+            }
+        }
+    }
+
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        Ok(Some(self.screen.create_entities(world)))
+    }
+
+    fn on_leave(&mut self, _world: &mut World) -> GameResult {
+        Ok(())
+    }
+}
@@ -1,25 +1,42 @@
 #![allow(dead_code)]
 
 use ggez::{Context, GameResult};
-use specs::{Entity, World, WorldExt};
+use ggez_extras::audio::{AudioManager, Sound};
+use log::{info, warn};
+use specs::{Entity, Join, World, WorldExt};
+
+use crate::components;
 
 pub mod countdown;
+pub mod graph;
+pub mod loading;
+pub mod pause;
 pub mod play;
 pub mod score;
+pub mod settings;
 pub mod title;
 
 pub use countdown::*;
+pub use graph::*;
+pub use loading::*;
+pub use pause::*;
 pub use play::*;
 pub use score::*;
+pub use settings::*;
 pub use title::*;
 
+/// How long the music takes to crossfade when the scene changes, in seconds.
+const MUSIC_FADE: f32 = 1.0;
+
 /// A command to change to a new scene, either by pushign a new one,
 /// popping one or replacing the current scene (pop and then push).
+/// An `Event` is resolved through the `SceneGraph` of the stack.
 pub enum SceneSwitch {
     None,
     Push(Box<dyn Scene>),
     Replace(Box<dyn Scene>),
     Pop,
+    Event(&'static str),
 }
 
 /// A trait for you to implement on a scene.
@@ -27,6 +44,12 @@ pub trait Scene {
     fn update(&mut self, world: &mut World, ctx: &mut Context) -> SceneSwitch;
     fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>>;
     fn on_leave(&mut self, world: &mut World) -> GameResult;
+
+    /// Whether the game stands still while the scene is on top, so the
+    /// camera, the particles and the sounds wait for it to leave.
+    fn pauses_game(&self) -> bool {
+        false
+    }
 }
 
 impl SceneSwitch {
@@ -43,12 +66,18 @@ impl SceneSwitch {
     {
         SceneSwitch::Push(Box::new(scene))
     }
+
+    pub fn event(name: &'static str) -> Self {
+        SceneSwitch::Event(name)
+    }
 }
 
 /// A stack of `Scene`'s, together with a context object.
 pub struct SceneStack {
     scenes: Vec<Box<dyn Scene>>,
     entities: Vec<Option<Vec<Entity>>>,
+    states: Vec<Option<&'static str>>,
+    graph: Option<SceneGraph>,
 }
 
 impl SceneStack {
@@ -56,17 +85,59 @@ impl SceneStack {
         Self {
             scenes: Vec::new(),
             entities: Vec::new(),
+            states: Vec::new(),
+            graph: None,
         }
     }
 
+    /// Uses the given graph to resolve `SceneSwitch::Event` switches.
+    pub fn with_graph(mut self, graph: SceneGraph) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    pub fn graph(&self) -> Option<&SceneGraph> {
+        self.graph.as_ref()
+    }
+
+    /// Returns the graph state of the current scene, if it was entered
+    /// through the graph.
+    pub fn current_state(&self) -> Option<&'static str> {
+        self.states.last().cloned().flatten()
+    }
+
     pub fn is_empty(&mut self) -> bool {
         self.scenes.len() == 0
     }
 
     /// Add a new scene to the top of the stack.
-    pub fn push(&mut self, mut scene: Box<dyn Scene>, world: &mut World) {
+    pub fn push(&mut self, scene: Box<dyn Scene>, world: &mut World) {
+        self.push_scene(None, scene, world);
+    }
+
+    /// Creates the scene for the given graph state and adds it to the
+    /// top of the stack; panics if the stack has no graph or the state
+    /// does not exist.
+    pub fn push_state(&mut self, state: &'static str, world: &mut World, ctx: &mut Context) {
+        let scene = self
+            .graph
+            .as_ref()
+            .expect("ERROR: Pushed a scene state without a scene graph.")
+            .create(state, world, ctx)
+            .expect("ERROR: Pushed an unknown scene state.");
+        info!("Entering scene state '{}'", state);
+        self.push_scene(Some(state), scene, world);
+    }
+
+    fn push_scene(
+        &mut self,
+        state: Option<&'static str>,
+        mut scene: Box<dyn Scene>,
+        world: &mut World,
+    ) {
         self.entities
             .push(scene.on_enter(world).expect("error pushing scene"));
+        self.states.push(state);
         self.scenes.push(scene);
     }
 
@@ -78,6 +149,7 @@ impl SceneStack {
             .pop()
             .expect("ERROR: Popped an empty scene stack.");
         scene.on_leave(world).expect("error popping scene");
+        self.states.pop();
 
         if let Some(Some(scene_entities)) = self.entities.pop() {
             let _ = world.delete_entities(scene_entities.as_slice());
@@ -96,7 +168,12 @@ impl SceneStack {
 
     /// Executes the given SceneSwitch command; if it is a pop or replace
     /// it returns `Some(old_scene)`, otherwise `None`
-    pub fn switch(&mut self, next_scene: SceneSwitch, world: &mut World) -> Option<Box<dyn Scene>> {
+    pub fn switch(
+        &mut self,
+        next_scene: SceneSwitch,
+        world: &mut World,
+        ctx: &mut Context,
+    ) -> Option<Box<dyn Scene>> {
         match next_scene {
             SceneSwitch::None => None,
             SceneSwitch::Pop => {
@@ -112,6 +189,42 @@ impl SceneStack {
                 self.push(s, world);
                 Some(old_scene)
             }
+            SceneSwitch::Event(event) => self.transition(event, world, ctx),
+        }
+    }
+
+    /// Replaces the current scene by the target of the graph transition
+    /// for `event`; unknown events are logged and ignored.
+    fn transition(
+        &mut self,
+        event: &'static str,
+        world: &mut World,
+        ctx: &mut Context,
+    ) -> Option<Box<dyn Scene>> {
+        let from = self.current_state();
+        let next = match (&self.graph, from) {
+            (Some(graph), Some(from)) => graph
+                .target(from, event)
+                .and_then(|to| graph.create(to, world, ctx).map(|scene| (to, scene))),
+            _ => None,
+        };
+
+        match next {
+            Some((to, scene)) => {
+                info!(
+                    "Scene transition '{}' --{}--> '{}'",
+                    from.unwrap_or_default(),
+                    event,
+                    to
+                );
+                let old_scene = self.pop(world);
+                self.push_scene(Some(to), scene, world);
+                Some(old_scene)
+            }
+            None => {
+                warn!("No scene transition for event '{}' from {:?}", event, from);
+                None
+            }
         }
     }
 
@@ -127,6 +240,44 @@ impl SceneStack {
             current_scene.update(world, ctx)
         };
 
-        self.switch(next_scene, world);
+        self.switch(next_scene, world, ctx);
+    }
+}
+
+/// Crossfades to the music of a scene that is entered, or fades the music
+/// out for `None`.
+pub fn play_music(world: &World, music: Option<Sound>) {
+    let mut audio = world.write_resource::<AudioManager>();
+    match music {
+        Some(music) => audio.play_music(music, MUSIC_FADE),
+        None => audio.stop_music(MUSIC_FADE),
     }
 }
+
+/// Create the scene flow of the game: Loading → Title → Countdown → Play → Score → Countdown,
+/// with the settings reachable from the title.
+pub fn create_scene_graph() -> SceneGraph {
+    SceneGraph::new()
+        .state("loading", |_world, ctx| Box::new(LoadingScene::new(ctx)))
+        .state("title", |_world, _ctx| Box::new(TitleScene::new()))
+        .state("settings", |_world, _ctx| Box::new(SettingsScene::new()))
+        .state("countdown", |world, ctx| {
+            Box::new(CountdownScene::new(ctx, world))
+        })
+        .state("play", |world, ctx| Box::new(PlayScene::new(ctx, world)))
+        .state("score", |world, _ctx| {
+            let mut final_score = 0;
+            for score in (&world.read_storage::<components::Score>()).join() {
+                final_score = score.0;
+            }
+            Box::new(ScoreScene::new(final_score))
+        })
+        .transition("loading", "loaded", "title")
+        .transition("title", "start", "countdown")
+        .transition("title", "settings", "settings")
+        .transition("settings", "back", "title")
+        .transition("countdown", "done", "play")
+        .transition("play", "died", "score")
+        .transition("play", "quit", "title")
+        .transition("score", "restart", "countdown")
+}
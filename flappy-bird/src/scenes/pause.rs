@@ -0,0 +1,64 @@
+use ggez::Context;
+use ggez_extras::audio::AudioManager;
+use specs::{Builder, Entity, World, WorldExt};
+
+use crate::*;
+
+/// Shown on top of the play scene, which stands still until the game is
+/// resumed.
+pub struct PauseScene;
+
+impl PauseScene {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl scenes::Scene for PauseScene {
+    fn update(&mut self, world: &mut World, _ctx: &mut Context) -> scenes::SceneSwitch {
+        if world
+            .read_resource::<input::State>()
+            .get_button_released(input::Button::Pause)
+        {
+            scenes::SceneSwitch::Pop
+        } else {
+            scenes::SceneSwitch::None
+        }
+    }
+
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        world.write_resource::<AudioManager>().pause_music();
+
+        let font = world.read_resource::<Fonts>()[&FontType::Flappy];
+        Ok(Some(vec![world
+            .create_entity()
+            .with(components::Render {
+                visible: true,
+                layer: LAYER_UI,
+            })
+            .with(components::Text {
+                text: String::from("Paused"),
+                font,
+                font_size: 28.0,
+                color: graphics::WHITE,
+                align: Alignment::Centered,
+                effect: Some(TEXT_SHADOW),
+                ..Default::default()
+            })
+            .with(components::Size {
+                w: VIRTUAL_WIDTH,
+                h: f32::INFINITY,
+            })
+            .with(components::Position { x: 0.0, y: 100.0 })
+            .build()]))
+    }
+
+    fn on_leave(&mut self, world: &mut World) -> GameResult {
+        world.write_resource::<AudioManager>().resume_music();
+        Ok(())
+    }
+
+    fn pauses_game(&self) -> bool {
+        true
+    }
+}
@@ -1,6 +1,8 @@
 use ggez::Context;
+use ggez_extras::animation::AnimationSystem;
 use rand::Rng;
 use specs::{Dispatcher, DispatcherBuilder, Entity, Join, World, WorldExt};
+use std::f32::consts;
 
 use crate::*;
 
@@ -15,6 +17,7 @@ pub struct PlayScene {
     pipe_pairs: Vec<(Entity, Entity)>,
     last_y: f32,
     score_entity: Option<Entity>,
+    shown_score: Option<u8>,
 }
 
 impl scenes::Scene for PlayScene {
@@ -26,24 +29,25 @@ impl scenes::Scene for PlayScene {
             is_dead = dead.0;
         }
 
-        if world
-            .read_resource::<input::State>()
-            .get_button_released(input::Button::Enter)
-        {
-            scenes::SceneSwitch::replace(scenes::TitleScene::new())
+        let input_state = world.read_resource::<input::State>();
+        if input_state.get_button_released(input::Button::Enter) {
+            scenes::SceneSwitch::event("quit")
+        } else if input_state.get_button_released(input::Button::Pause) {
+            scenes::SceneSwitch::push(scenes::PauseScene::new())
         } else if is_dead {
-            let mut final_score = 0;
-            for score in (&world.read_storage::<components::Score>()).join() {
-                final_score = score.0;
-            }
-            scenes::SceneSwitch::replace(scenes::ScoreScene::new(final_score))
+            scenes::SceneSwitch::event("died")
         } else {
+            // Only touch the text when the score changed, since every
+            // change makes the renderer lay it out again.
             for score in (world.read_storage::<components::Score>()).join() {
-                world
-                    .write_storage::<components::Text>()
-                    .get_mut(self.score_entity.unwrap())
-                    .unwrap()
-                    .text = format!("Score: {}", score.0)
+                if self.shown_score != Some(score.0) {
+                    self.shown_score = Some(score.0);
+                    world
+                        .write_storage::<components::Text>()
+                        .get_mut(self.score_entity.unwrap())
+                        .unwrap()
+                        .text = format!("Score: {}", score.0)
+                }
             }
 
             scenes::SceneSwitch::None
@@ -51,7 +55,9 @@ impl scenes::Scene for PlayScene {
     }
 
     fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
-        let bird_image = world.read_resource::<Images>().bird.clone();
+        scenes::play_music(world, Some(world.read_resource::<Sounds>().music));
+
+        let bird_sheet = world.read_resource::<Images>().bird.clone();
 
         let entities: Vec<Entity> = vec![
             self.create_score_text(world),
@@ -60,17 +66,35 @@ impl scenes::Scene for PlayScene {
                 .with(components::Player)
                 .with(components::Score(0))
                 .with(components::Dead(false))
-                .with(components::Render { visible: true })
-                .with(components::Image { image: bird_image })
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_PLAYER,
+                })
+                .with(components::Sprite::new(bird_sheet, 1))
+                // The wings go up, level, down and level again.
+                .with(components::Animation::new(
+                    vec![0, 1, 2, 1],
+                    0.08,
+                    components::AnimationMode::Loop,
+                ))
                 .with(components::Size {
                     w: BIRD_WIDTH,
                     h: BIRD_HEIGHT,
                 })
                 .with(components::Velocity { x: 0.0, y: 0.0 })
+                // Feathers fall off behind the bird when it flaps.
+                .with(
+                    components::ParticleEmitter::new(0.4..0.8)
+                        .with_speed(20.0..50.0)
+                        .with_angle(consts::FRAC_PI_2..consts::PI)
+                        .with_gravity(0.0, 120.0)
+                        .with_offset(BIRD_WIDTH / 2.0 - 8.0, BIRD_HEIGHT / 2.0)
+                        .with_sizes(3.0, 1.0)
+                        .with_colors(graphics::WHITE, graphics::Color::new(1.0, 1.0, 1.0, 0.0)),
+                )
                 .with(components::Position {
                     x: VIRTUAL_WIDTH / 2.0 - 8.0,
                     y: VIRTUAL_HEIGHT / 2.0 - 8.0,
-                    z: 1,
                 })
                 .build(),
         ];
@@ -98,12 +122,14 @@ impl PlayScene {
             pipe_pairs: Vec::new(),
             last_y: -PIPE_HEIGHT + rng.gen_range(0.0, 80.0) + 20.0,
             score_entity: None,
+            shown_score: None,
         }
     }
 
     fn register_systems() -> specs::Dispatcher<'static, 'static> {
         DispatcherBuilder::new()
             .with(systems::ParallaxSystem, "parallax", &[])
+            .with(AnimationSystem, "animation", &[])
             .with(systems::PlayerSystem, "player", &[])
             .with(systems::PipeSystem::new(), "pipe", &[])
             .with(
@@ -115,23 +141,26 @@ impl PlayScene {
     }
 
     fn create_score_text(&mut self, world: &mut World) -> Entity {
+        let font = world.read_resource::<Fonts>()[&FontType::Flappy];
         let entity = world
             .create_entity()
-            .with(components::Render { visible: true })
+            .with(components::Render {
+                visible: true,
+                layer: LAYER_UI,
+            })
             .with(components::Text {
                 text: String::from("Score: 0"),
-                font: FontType::Flappy,
+                font,
                 font_size: 28.0,
                 color: graphics::WHITE,
                 align: Alignment::Centered,
+                effect: Some(TEXT_SHADOW),
+                ..Default::default()
             })
-            .with(components::Position {
-                x: 8.0,
-                y: 8.0,
-                z: 1,
-            })
+            .with(components::Position { x: 8.0, y: 8.0 })
             .build();
         self.score_entity = Some(entity.clone());
+        self.shown_score = Some(0);
         entity
     }
 }
@@ -0,0 +1,120 @@
+use ggez::Context;
+use ggez_extras::settings::{Settings, SettingsMenu};
+use log::warn;
+use specs::{Builder, Entity, World, WorldExt};
+
+use crate::*;
+
+/// Lists the settings on top of the background; Up and Down select one,
+/// Left and Right change it.
+pub struct SettingsScene {
+    menu: Option<SettingsMenu>,
+}
+
+impl SettingsScene {
+    pub fn new() -> Self {
+        Self { menu: None }
+    }
+}
+
+impl scenes::Scene for SettingsScene {
+    fn update(&mut self, world: &mut World, ctx: &mut Context) -> scenes::SceneSwitch {
+        let (back, up, down, step) = {
+            let input = world.read_resource::<input::State>();
+            let step = if input.get_button_pressed(input::Button::Left) {
+                -1
+            } else if input.get_button_pressed(input::Button::Right) {
+                1
+            } else {
+                0
+            };
+            (
+                input.get_button_released(input::Button::Settings),
+                input.get_button_pressed(input::Button::Up),
+                input.get_button_pressed(input::Button::Down),
+                step,
+            )
+        };
+        if back {
+            return scenes::SceneSwitch::event("back");
+        }
+
+        if let Some(menu) = &mut self.menu {
+            if up {
+                menu.select_previous(world);
+            }
+            if down {
+                menu.select_next(world);
+            }
+            if step != 0 {
+                if let Err(e) = menu.change(world, ctx, step) {
+                    warn!("Unable to change the setting: {}", e);
+                }
+            }
+        }
+        scenes::SceneSwitch::None
+    }
+
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        let (flappy, retro) = {
+            let fonts = world.read_resource::<Fonts>();
+            (fonts[&FontType::Flappy], fonts[&FontType::Retro])
+        };
+        let mut entities = vec![
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: String::from("Settings"),
+                    font: flappy,
+                    font_size: 28.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    effect: Some(TEXT_SHADOW),
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position { x: 0.0, y: 32.0 })
+                .build(),
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: String::from("Arrows to change, F2 to go back"),
+                    font: flappy,
+                    font_size: 14.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    effect: Some(TEXT_SHADOW),
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position {
+                    x: 0.0,
+                    y: VIRTUAL_HEIGHT - 48.0,
+                })
+                .build(),
+        ];
+
+        let mut menu = SettingsMenu::new(LAYER_UI, retro, 8.0, VIRTUAL_WIDTH);
+        entities.extend(menu.create_entities(world, 90.0));
+        self.menu = Some(menu);
+        Ok(Some(entities))
+    }
+
+    fn on_leave(&mut self, world: &mut World) -> GameResult {
+        world.read_resource::<Settings>().save(GAME_ID, AUTHOR)
+    }
+}
@@ -12,58 +12,68 @@ impl TitleScene {
 }
 
 impl scenes::Scene for TitleScene {
-    fn update(&mut self, world: &mut World, ctx: &mut Context) -> scenes::SceneSwitch {
+    fn update(&mut self, world: &mut World, _ctx: &mut Context) -> scenes::SceneSwitch {
         if world
             .read_resource::<input::State>()
             .get_button_released(input::Button::Enter)
         {
-            scenes::SceneSwitch::replace(scenes::CountdownScene::new(ctx, world))
+            scenes::SceneSwitch::event("start")
+        } else if world
+            .read_resource::<input::State>()
+            .get_button_released(input::Button::Settings)
+        {
+            scenes::SceneSwitch::event("settings")
         } else {
             scenes::SceneSwitch::None
         }
     }
 
     fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        scenes::play_music(world, Some(world.read_resource::<Sounds>().music));
+
+        let font = world.read_resource::<Fonts>()[&FontType::Flappy];
         Ok(Some(vec![
             world
                 .create_entity()
-                .with(components::Render { visible: true })
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
                 .with(components::Text {
                     text: String::from("Fifty Bird"),
-                    font: FontType::Flappy,
+                    font,
                     font_size: 28.0,
                     color: graphics::WHITE,
                     align: Alignment::Centered,
+                    effect: Some(TEXT_SHADOW),
+                    ..Default::default()
                 })
                 .with(components::Size {
                     w: VIRTUAL_WIDTH,
                     h: f32::INFINITY,
                 })
-                .with(components::Position {
-                    x: 0.0,
-                    y: 64.0,
-                    z: 1,
-                })
+                .with(components::Position { x: 0.0, y: 64.0 })
                 .build(),
             world
                 .create_entity()
-                .with(components::Render { visible: true })
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
                 .with(components::Text {
-                    text: String::from("Press Enter"),
-                    font: FontType::Flappy,
+                    text: String::from("Press Enter, F2 for settings"),
+                    font,
                     font_size: 14.0,
                     color: graphics::WHITE,
                     align: Alignment::Centered,
+                    effect: Some(TEXT_SHADOW),
+                    ..Default::default()
                 })
                 .with(components::Size {
                     w: VIRTUAL_WIDTH,
                     h: f32::INFINITY,
                 })
-                .with(components::Position {
-                    x: 0.0,
-                    y: 100.0,
-                    z: 1,
-                })
+                .with(components::Position { x: 0.0, y: 100.0 })
                 .build(),
         ]))
     }
@@ -1,11 +1,11 @@
 pub mod parallax;
 pub mod pipe;
 pub mod player;
-pub mod render;
 pub mod score;
+#[cfg(test)]
+mod testing;
 
 pub use parallax::*;
 pub use pipe::*;
 pub use player::*;
-pub use render::*;
 pub use score::*;
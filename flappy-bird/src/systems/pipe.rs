@@ -70,7 +70,13 @@ impl<'s> System<'s> for PipeSystem {
                     },
                     &mut pipe_storage,
                 )
-                .with(components::Render { visible: true }, &mut render_storage)
+                .with(
+                    components::Render {
+                        visible: true,
+                        layer: LAYER_PIPES,
+                    },
+                    &mut render_storage,
+                )
                 .with(
                     components::Image {
                         image: images_resource.pipe.clone(),
@@ -96,7 +102,6 @@ impl<'s> System<'s> for PipeSystem {
                     components::Position {
                         x: VIRTUAL_WIDTH + 64.0,
                         y: pipe_y,
-                        z: 1,
                     },
                     &mut pos_storage,
                 )
@@ -111,7 +116,13 @@ impl<'s> System<'s> for PipeSystem {
                     },
                     &mut pipe_storage,
                 )
-                .with(components::Render { visible: true }, &mut render_storage)
+                .with(
+                    components::Render {
+                        visible: true,
+                        layer: LAYER_PIPES,
+                    },
+                    &mut render_storage,
+                )
                 .with(
                     components::Image {
                         image: images_resource.pipe.clone(),
@@ -129,7 +140,6 @@ impl<'s> System<'s> for PipeSystem {
                     components::Position {
                         x: VIRTUAL_WIDTH + 64.0,
                         y: pipe_y + PIPE_HEIGHT + 90.0,
-                        z: 1,
                     },
                     &mut pos_storage,
                 )
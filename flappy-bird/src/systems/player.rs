@@ -1,4 +1,5 @@
-use specs::{Join, Read, WriteExpect, ReadExpect, ReadStorage, System, WriteStorage};
+use ggez_extras::audio::SoundQueue;
+use specs::{Join, Read, Write, ReadExpect, ReadStorage, System, WriteStorage};
 
 use crate::*;
 
@@ -7,20 +8,25 @@ const GRAVITY: f32 = 18.0;
 pub struct PlayerSystem;
 impl<'s> System<'s> for PlayerSystem {
     type SystemData = (
-        WriteExpect<'s, Sounds>,
+        ReadExpect<'s, Sounds>,
+        Write<'s, SoundQueue>,
         ReadExpect<'s, input::State>,
         ReadStorage<'s, components::Player>,
         WriteStorage<'s, components::Position>,
         WriteStorage<'s, components::Velocity>,
+        WriteStorage<'s, components::ParticleEmitter>,
         Read<'s, DeltaTime>,
     );
 
-    fn run(&mut self, (mut sounds, input_state, players, mut positions, mut velocity, dt): Self::SystemData) {
-        for (_, pos, vel) in (&players, &mut positions, &mut velocity).join() {
+    fn run(&mut self, (sounds, mut sound_queue, input_state, players, mut positions, mut velocity, mut emitters, dt): Self::SystemData) {
+        for (_, pos, vel, emitter) in (&players, &mut positions, &mut velocity, (&mut emitters).maybe()).join() {
             vel.y += GRAVITY * dt.delta;
             if input_state.get_button_pressed(input::Button::Space) {
                 vel.y = -4.0;
-                let _ = sounds.jump.play();
+                sound_queue.play(sounds.jump);
+                if let Some(emitter) = emitter {
+                    emitter.burst(4);
+                }
             }
             pos.y += vel.y;
         }
@@ -1,4 +1,8 @@
-use specs::{Join, WriteExpect, ReadStorage, System, WriteStorage};
+use ggez::graphics::Rect;
+use ggez_extras::audio::SoundQueue;
+use ggez_extras::camera::Camera;
+use ggez_extras::render::DebugOverlay;
+use specs::{Join, Write, ReadExpect, ReadStorage, System, WriteStorage};
 
 use crate::*;
 
@@ -12,7 +16,10 @@ impl ScoreSystem {
 
 impl<'s> System<'s> for ScoreSystem {
     type SystemData = (
-        WriteExpect<'s, Sounds>,
+        ReadExpect<'s, Sounds>,
+        Write<'s, SoundQueue>,
+        Option<Write<'s, Camera>>,
+        Option<Write<'s, DebugOverlay>>,
         ReadStorage<'s, components::Player>,
         WriteStorage<'s, components::Pipe>,
         ReadStorage<'s, components::Position>,
@@ -23,7 +30,7 @@ impl<'s> System<'s> for ScoreSystem {
 
     fn run(
         &mut self,
-        (mut sounds, player_storage, mut pipe_storage, pos_storage, size_storage, mut score_storage, mut dead_storage): Self::SystemData,
+        (sounds, mut sound_queue, mut camera, mut debug, player_storage, mut pipe_storage, pos_storage, size_storage, mut score_storage, mut dead_storage): Self::SystemData,
     ) {
         for (pipe, pipe_pos, pipe_size) in (&mut pipe_storage, &pos_storage, &size_storage).join() {
             for (_, player_pos, player_size, score, dead) in (&player_storage, &pos_storage, &size_storage, &mut score_storage, &mut dead_storage).join()
@@ -34,14 +41,20 @@ impl<'s> System<'s> for ScoreSystem {
                 {
                     pipe.scored = true;
                     score.0 += 1;
-                    let _ = sounds.score.play();
+                    sound_queue.play(sounds.score);
                 }
 
                 if (player_pos.x + 2.0) + (player_size.w - 4.0) >= pipe_pos.x && player_pos.x + 2.0 <= pipe_pos.x + pipe_size.w {
                     if (player_pos.y + 2.0) + (player_size.h - 4.0) >= pipe_pos.y && player_pos.y + 2.0 <= pipe_pos.y + pipe_size.h {
                         dead.0 = true;
-                        let _ = sounds.explosion.play();
-                        let _ = sounds.hurt.play();
+                        if let Some(debug) = &mut debug {
+                            debug.add_contact(Rect::new(player_pos.x + 2.0, player_pos.y + 2.0, player_size.w - 4.0, player_size.h - 4.0));
+                        }
+                        if let Some(camera) = &mut camera {
+                            camera.add_trauma(0.6);
+                        }
+                        sound_queue.play(sounds.explosion);
+                        sound_queue.play(sounds.hurt);
                     }
                 }
             }
@@ -50,8 +63,83 @@ impl<'s> System<'s> for ScoreSystem {
         for (_, player_pos, dead) in (&player_storage, &pos_storage, &mut dead_storage).join() {
             if player_pos.y > VIRTUAL_HEIGHT - 15.0 {
                 dead.0 = true;
-                let _ = sounds.hurt.play();
+                sound_queue.play(sounds.hurt);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use specs::{Builder, Entity, RunNow, World, WorldExt};
+
+    use super::*;
+    use crate::systems::testing::{create_world, queued_sounds};
+
+    fn create_player(world: &mut World, y: f32) -> Entity {
+        world
+            .create_entity()
+            .with(components::Player)
+            .with(components::Position { x: 100.0, y })
+            .with(components::Size {
+                w: BIRD_WIDTH,
+                h: BIRD_HEIGHT,
+            })
+            .with(components::Score(0))
+            .with(components::Dead(false))
+            .build()
+    }
+
+    fn create_pipe(world: &mut World, x: f32, y: f32, side: PipeSide) {
+        world
+            .create_entity()
+            .with(components::Pipe {
+                scored: false,
+                side,
+            })
+            .with(components::Position { x, y })
+            .with(components::Size {
+                w: PIPE_WIDTH,
+                h: PIPE_HEIGHT,
+            })
+            .build();
+    }
+
+    #[test]
+    fn queues_the_score_sound_for_a_passed_pipe() {
+        let mut world = create_world();
+        let player = create_player(&mut world, 100.0);
+        create_pipe(&mut world, 100.0 - PIPE_WIDTH - 10.0, 0.0, PipeSide::Top);
+
+        ScoreSystem::new().run_now(&world);
+
+        let score = world.read_resource::<Sounds>().score;
+        assert_eq!(queued_sounds(&world), vec![score]);
+        assert_eq!(world.read_storage::<components::Score>().get(player).unwrap().0, 1);
+    }
+
+    #[test]
+    fn queues_the_crash_sounds_when_hitting_a_pipe() {
+        let mut world = create_world();
+        let player = create_player(&mut world, 100.0);
+        create_pipe(&mut world, 100.0, 50.0, PipeSide::Bottom);
+
+        ScoreSystem::new().run_now(&world);
+
+        let sounds = world.read_resource::<Sounds>().clone();
+        assert_eq!(queued_sounds(&world), vec![sounds.explosion, sounds.hurt]);
+        assert!(world.read_storage::<components::Dead>().get(player).unwrap().0);
+    }
+
+    #[test]
+    fn queues_the_hurt_sound_when_hitting_the_ground() {
+        let mut world = create_world();
+        let player = create_player(&mut world, VIRTUAL_HEIGHT);
+
+        ScoreSystem::new().run_now(&world);
+
+        let hurt = world.read_resource::<Sounds>().hurt;
+        assert_eq!(queued_sounds(&world), vec![hurt]);
+        assert!(world.read_storage::<components::Dead>().get(player).unwrap().0);
+    }
+}
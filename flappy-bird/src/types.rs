@@ -1,25 +1,45 @@
-use ggez::{audio, graphics};
+use ggez::{graphics, GameResult};
+use ggez_extras::assets::Assets;
+use ggez_extras::audio::Sound;
+use ggez_extras::render::SpriteSheet;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-#[derive(Clone, Debug, Default)]
-pub struct DeltaTime {
-    pub delta: f32,
-}
+pub use ggez_extras::render::{Alignment, TextEffect};
+pub use ggez_extras::types::DeltaTime;
 
 #[derive(Clone, Debug)]
 pub struct Images {
-    pub background: graphics::Image,
-    pub bird: graphics::Image,
-    pub ground: graphics::Image,
-    pub pipe: graphics::Image,
+    pub background: Arc<graphics::Image>,
+    /// The bird with its wings up, level and down.
+    pub bird: Arc<SpriteSheet>,
+    pub ground: Arc<graphics::Image>,
+    pub pipe: Arc<graphics::Image>,
+}
+
+impl Images {
+    pub fn load(assets: &Assets) -> GameResult<Self> {
+        Ok(Self {
+            background: assets.image("background")?,
+            bird: Arc::new(SpriteSheet::from_grid(
+                assets.image("bird")?,
+                crate::BIRD_WIDTH as u16,
+                crate::BIRD_HEIGHT as u16,
+            )?),
+            ground: assets.image("ground")?,
+            pipe: assets.image("pipe")?,
+        })
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Sounds {
-    pub explosion: audio::Source,
-    pub hurt: audio::Source,
-    pub jump: audio::Source,
-    pub score: audio::Source,
+    pub explosion: Sound,
+    pub hurt: Sound,
+    pub jump: Sound,
+    pub score: Sound,
+    /// The background music, looped by the scenes that play it.
+    pub music: Sound,
 }
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
@@ -30,12 +50,11 @@ pub enum FontType {
 
 pub type Fonts = HashMap<FontType, graphics::Font>;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-#[allow(dead_code)]
-pub enum Alignment {
-    Left,
-    Right,
-    Centered,
+pub fn load_fonts(assets: &Assets) -> GameResult<Fonts> {
+    let mut fonts = Fonts::new();
+    fonts.insert(FontType::Retro, assets.font("retro")?);
+    fonts.insert(FontType::Flappy, assets.font("flappy")?);
+    Ok(fonts)
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
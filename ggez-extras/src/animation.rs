@@ -0,0 +1,235 @@
+//! Frame based animation of `Sprite` components.
+use specs::{
+    shrev::EventChannel, Component, Entities, Entity, Join, Read, System, VecStorage, World,
+    WorldExt, Write, WriteStorage,
+};
+
+use crate::render::Sprite;
+use crate::types::DeltaTime;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AnimationMode {
+    /// Starts over at the first frame after the last one.
+    Loop,
+    /// Plays forwards and backwards again, forever.
+    PingPong,
+    /// Stops at the last frame and emits `AnimationEvent::Finished`.
+    Once,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AnimationEvent {
+    Finished(Entity),
+}
+
+/// Plays a list of sprite sheet frames, each shown for its own duration in
+/// seconds. `speed` scales the playback rate.
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct Animation {
+    pub frames: Vec<usize>,
+    pub durations: Vec<f32>,
+    pub mode: AnimationMode,
+    pub speed: f32,
+    pub playing: bool,
+    current: usize,
+    timer: f32,
+    reverse: bool,
+}
+
+impl Animation {
+    /// Creates an animation that shows every frame for `frame_duration` seconds.
+    pub fn new(frames: Vec<usize>, frame_duration: f32, mode: AnimationMode) -> Self {
+        let durations = vec![frame_duration; frames.len()];
+        Self {
+            frames,
+            durations,
+            mode,
+            speed: 1.0,
+            playing: true,
+            current: 0,
+            timer: 0.0,
+            reverse: false,
+        }
+    }
+
+    /// Sets the duration of every frame separately.
+    pub fn with_durations(mut self, durations: Vec<f32>) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Returns the sprite sheet frame that should currently be shown.
+    pub fn frame(&self) -> usize {
+        self.frames.get(self.current).cloned().unwrap_or(0)
+    }
+
+    /// Starts playing again from the first frame.
+    pub fn restart(&mut self) {
+        self.current = 0;
+        self.timer = 0.0;
+        self.reverse = false;
+        self.playing = true;
+    }
+
+    /// Advances the animation by `delta` seconds; returns true when an
+    /// animation in `Once` mode has just finished.
+    pub fn advance(&mut self, delta: f32) -> bool {
+        if !self.playing || self.frames.is_empty() {
+            return false;
+        }
+
+        self.timer += delta * self.speed;
+        loop {
+            let duration = self.durations.get(self.current).cloned().unwrap_or(0.0);
+            if duration <= 0.0 || self.timer < duration {
+                return false;
+            }
+            self.timer -= duration;
+
+            let last = self.frames.len() - 1;
+            match self.mode {
+                AnimationMode::Loop => self.current = (self.current + 1) % self.frames.len(),
+                AnimationMode::PingPong if last > 0 => {
+                    if self.reverse && self.current == 0 {
+                        self.reverse = false;
+                    } else if !self.reverse && self.current == last {
+                        self.reverse = true;
+                    }
+                    if self.reverse {
+                        self.current -= 1;
+                    } else {
+                        self.current += 1;
+                    }
+                }
+                AnimationMode::PingPong => {}
+                AnimationMode::Once => {
+                    if self.current == last {
+                        self.playing = false;
+                        self.timer = 0.0;
+                        return true;
+                    }
+                    self.current += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Advances all animations and shows their current frame on the `Sprite`.
+pub struct AnimationSystem;
+impl<'s> System<'s> for AnimationSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Animation>,
+        WriteStorage<'s, Sprite>,
+        Read<'s, DeltaTime>,
+        Write<'s, EventChannel<AnimationEvent>>,
+    );
+
+    fn run(&mut self, (entities, mut animations, mut sprites, dt, mut events): Self::SystemData) {
+        for (e, animation, sprite) in (&entities, &mut animations, &mut sprites).join() {
+            if animation.advance(dt.delta) {
+                events.single_write(AnimationEvent::Finished(e));
+            }
+            sprite.frame = animation.frame();
+        }
+    }
+}
+
+pub fn register(world: &mut World) {
+    world.register::<Animation>();
+    world.insert(EventChannel::<AnimationEvent>::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advances_a_frame_per_duration() {
+        let mut animation = Animation::new(vec![4, 5, 6], 0.25, AnimationMode::Loop);
+        assert_eq!(animation.frame(), 4);
+        animation.advance(0.125);
+        assert_eq!(animation.frame(), 4);
+        animation.advance(0.125);
+        assert_eq!(animation.frame(), 5);
+        // A long step skips over as many frames as it covers.
+        animation.advance(0.25);
+        assert_eq!(animation.frame(), 6);
+    }
+
+    #[test]
+    fn uses_the_duration_of_every_frame_and_the_speed() {
+        let mut animation = Animation::new(vec![0, 1], 0.0, AnimationMode::Loop)
+            .with_durations(vec![0.5, 0.25])
+            .with_speed(2.0);
+        animation.advance(0.125);
+        assert_eq!(animation.frame(), 0);
+        animation.advance(0.125);
+        assert_eq!(animation.frame(), 1);
+        animation.advance(0.125);
+        assert_eq!(animation.frame(), 0);
+    }
+
+    #[test]
+    fn loops_back_to_the_first_frame() {
+        let mut animation = Animation::new(vec![0, 1, 2], 0.25, AnimationMode::Loop);
+        let frames: Vec<usize> = (0..5)
+            .map(|_| {
+                assert!(!animation.advance(0.25));
+                animation.frame()
+            })
+            .collect();
+        assert_eq!(frames, vec![1, 2, 0, 1, 2]);
+        assert!(animation.playing);
+    }
+
+    #[test]
+    fn ping_pongs_between_the_ends() {
+        let mut animation = Animation::new(vec![0, 1, 2], 0.25, AnimationMode::PingPong);
+        let frames: Vec<usize> = (0..6)
+            .map(|_| {
+                animation.advance(0.25);
+                animation.frame()
+            })
+            .collect();
+        assert_eq!(frames, vec![1, 2, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn stops_at_the_last_frame_once() {
+        let mut animation = Animation::new(vec![0, 1, 2], 0.25, AnimationMode::Once);
+        assert!(!animation.advance(0.25));
+        assert!(!animation.advance(0.25));
+        assert_eq!(animation.frame(), 2);
+        // The last frame is shown for its duration before it finishes.
+        assert!(animation.advance(0.25));
+        assert!(!animation.playing);
+        assert_eq!(animation.frame(), 2);
+
+        assert!(!animation.advance(1.0));
+        assert_eq!(animation.frame(), 2);
+
+        animation.restart();
+        assert!(animation.playing);
+        assert_eq!(animation.frame(), 0);
+    }
+
+    #[test]
+    fn stands_still_when_paused_or_empty() {
+        let mut animation = Animation::new(vec![0, 1], 0.25, AnimationMode::Loop);
+        animation.playing = false;
+        assert!(!animation.advance(1.0));
+        assert_eq!(animation.frame(), 0);
+
+        let mut empty = Animation::new(Vec::new(), 0.25, AnimationMode::Once);
+        assert!(!empty.advance(1.0));
+        assert_eq!(empty.frame(), 0);
+    }
+}
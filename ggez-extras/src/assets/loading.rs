@@ -0,0 +1,287 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use ggez::{graphics, Context, GameError, GameResult};
+use log::error;
+use specs::{Builder, Entity, World, WorldExt};
+
+use super::{Assets, Manifest, SoundEntry};
+use crate::audio::AudioManager;
+use crate::render::{Alignment, Position, Render, Shape, ShapeKind, Size, Text};
+
+/// An asset of the manifest that was not tried yet.
+#[derive(Debug)]
+enum Pending {
+    Image { name: String, path: String },
+    Font { name: String, path: String },
+    Sound { name: String, entry: SoundEntry },
+}
+
+/// Loads the assets of a manifest a few at a time, so the game can show
+/// the progress in between. Every asset is tried, and the errors of the
+/// ones that fail are collected.
+#[derive(Debug)]
+pub struct AssetLoader {
+    pending: VecDeque<Pending>,
+    total: usize,
+    errors: Vec<String>,
+}
+
+impl AssetLoader {
+    pub fn new(manifest: &Manifest) -> Self {
+        let mut pending = VecDeque::new();
+        pending.extend(manifest.images.iter().map(|(name, path)| Pending::Image {
+            name: name.clone(),
+            path: path.clone(),
+        }));
+        pending.extend(manifest.fonts.iter().map(|(name, path)| Pending::Font {
+            name: name.clone(),
+            path: path.clone(),
+        }));
+        pending.extend(manifest.sounds.iter().map(|(name, entry)| Pending::Sound {
+            name: name.clone(),
+            entry: entry.clone(),
+        }));
+
+        Self {
+            total: pending.len(),
+            pending,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Reads the manifest file at `path`. A manifest that cannot be read
+    /// leaves nothing to load and is reported as the error.
+    pub fn from_path(ctx: &mut Context, path: &str) -> Self {
+        match Manifest::load(ctx, path) {
+            Ok(manifest) => Self::new(&manifest),
+            Err(e) => Self {
+                pending: VecDeque::new(),
+                total: 0,
+                errors: vec![e.to_string()],
+            },
+        }
+    }
+
+    /// Loads the next asset into `assets`. Returns `false` once all assets
+    /// were tried.
+    pub fn load_next(
+        &mut self,
+        ctx: &mut Context,
+        assets: &mut Assets,
+        audio: &mut AudioManager,
+    ) -> bool {
+        match self.pending.pop_front() {
+            Some(Pending::Image { name, path }) => {
+                let handle = assets.load_image(ctx, &path);
+                assets.add_name(&name, &path, handle, &mut self.errors);
+            }
+            Some(Pending::Font { name, path }) => {
+                let handle = assets.load_font(ctx, &path);
+                assets.add_name(&name, &path, handle, &mut self.errors);
+            }
+            Some(Pending::Sound { name, entry }) => {
+                let handle = assets.load_sound(ctx, audio, &entry.path, entry.bus, entry.options);
+                assets.add_name(&name, &entry.path, handle, &mut self.errors);
+            }
+            None => return false,
+        }
+        true
+    }
+
+    /// Loads assets until all of them were tried or `budget` has passed.
+    pub fn load_for(
+        &mut self,
+        ctx: &mut Context,
+        assets: &mut Assets,
+        audio: &mut AudioManager,
+        budget: Duration,
+    ) {
+        let start = Instant::now();
+        while start.elapsed() < budget && self.load_next(ctx, assets, audio) {}
+    }
+
+    /// Returns the part of the assets that was tried, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.total - self.pending.len()) as f32 / self.total as f32
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Returns one error that lists every asset that failed to load.
+    pub fn result(&self) -> GameResult {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(GameError::ResourceLoadError(format!(
+                "Unable to load {} assets:\n{}",
+                self.errors.len(),
+                self.errors.join("\n")
+            )))
+        }
+    }
+}
+
+const BAR_HEIGHT: f32 = 8.0;
+
+/// How far a `LoadingScreen` got.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LoadingState {
+    Loading,
+    Loaded,
+    Failed,
+}
+
+/// A progress bar that fills while an `AssetLoader` loads into the `Assets`
+/// resource, with the errors shown below it if loading fails. A loading
+/// scene creates its entities when it is entered and updates it until the
+/// assets are loaded.
+#[derive(Debug)]
+pub struct LoadingScreen {
+    loader: AssetLoader,
+    layer: &'static str,
+    width: f32,
+    height: f32,
+    /// How long a single update may spend loading.
+    budget: Duration,
+    bar: Option<Entity>,
+    message: Option<Entity>,
+    failed: bool,
+}
+
+impl LoadingScreen {
+    /// Creates a loading screen drawn on `layer` of a screen `width` by
+    /// `height` large.
+    pub fn new(loader: AssetLoader, layer: &'static str, width: f32, height: f32) -> Self {
+        Self {
+            loader,
+            layer,
+            width,
+            height,
+            budget: Duration::from_millis(10),
+            bar: None,
+            message: None,
+            failed: false,
+        }
+    }
+
+    /// Sets how long a single update may spend loading; a longer time
+    /// loads faster but updates the screen less often.
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Creates the bar and the error message, and returns their entities.
+    pub fn create_entities(&mut self, world: &mut World) -> Vec<Entity> {
+        let bar_w = self.width / 2.0;
+        let x = (self.width - bar_w) / 2.0;
+        let y = (self.height - BAR_HEIGHT) / 2.0;
+
+        let frame = world
+            .create_entity()
+            .with(Render {
+                visible: true,
+                layer: self.layer,
+            })
+            .with(Shape::rectangle(bar_w + 4.0, BAR_HEIGHT + 4.0, graphics::WHITE).stroke(1.0))
+            .with(Position {
+                x: x - 2.0,
+                y: y - 2.0,
+            })
+            .build();
+        let bar = world
+            .create_entity()
+            .with(Render {
+                visible: true,
+                layer: self.layer,
+            })
+            .with(Shape::rectangle(1.0, BAR_HEIGHT, graphics::WHITE))
+            .with(Position { x, y })
+            .build();
+        let message = world
+            .create_entity()
+            .with(Render {
+                visible: true,
+                layer: self.layer,
+            })
+            .with(Text {
+                font_size: 10.0,
+                color: graphics::Color::new(1.0, 0.3, 0.3, 1.0),
+                align: Alignment::Centered,
+                wrap: true,
+                ..Default::default()
+            })
+            .with(Size {
+                w: self.width - 20.0,
+                h: f32::INFINITY,
+            })
+            .with(Position {
+                x: 10.0,
+                y: y + BAR_HEIGHT + 12.0,
+            })
+            .build();
+
+        self.bar = Some(bar);
+        self.message = Some(message);
+        vec![frame, bar, message]
+    }
+
+    /// Loads assets for the length of the budget and fills the bar. Once
+    /// every asset was tried, the screen is either loaded or shows the
+    /// errors of the assets that failed.
+    pub fn update(&mut self, world: &mut World, ctx: &mut Context) -> LoadingState {
+        if self.failed {
+            return LoadingState::Failed;
+        }
+
+        {
+            let mut assets = world.write_resource::<Assets>();
+            let mut audio = world.write_resource::<AudioManager>();
+            self.loader
+                .load_for(ctx, &mut assets, &mut audio, self.budget);
+        }
+        self.show_progress(world);
+
+        if !self.loader.is_done() {
+            return LoadingState::Loading;
+        }
+        match self.loader.result() {
+            Ok(()) => LoadingState::Loaded,
+            Err(e) => {
+                self.fail(world, &e);
+                LoadingState::Failed
+            }
+        }
+    }
+
+    /// Shows `error` and stops loading, also for errors of setting the game
+    /// up with the loaded assets.
+    pub fn fail(&mut self, world: &mut World, error: &GameError) {
+        error!("{}", error);
+        self.failed = true;
+
+        let mut texts = world.write_storage::<Text>();
+        if let Some(text) = self.message.and_then(|message| texts.get_mut(message)) {
+            text.text = error.to_string();
+        }
+    }
+
+    fn show_progress(&self, world: &mut World) {
+        let w = (self.width / 2.0 * self.loader.progress()).max(1.0);
+        let mut shapes = world.write_storage::<Shape>();
+        if let Some(shape) = self.bar.and_then(|bar| shapes.get_mut(bar)) {
+            shape.kind = ShapeKind::Rectangle { w, h: BAR_HEIGHT };
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Loads the images, fonts and sounds a game lists in a manifest file and
+//! hands out typed handles to them.
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use ggez::{filesystem, graphics, Context, GameError, GameResult};
+use serde_derive::Deserialize;
+
+use crate::audio::{AudioManager, Bus, Sound, SoundOptions};
+
+mod loading;
+mod watcher;
+
+pub use loading::*;
+pub use watcher::AssetWatcher;
+
+fn default_bus() -> Bus {
+    Bus::Sfx
+}
+
+/// A sound in the manifest, played on the SFX bus unless it says otherwise.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SoundEntry {
+    pub path: String,
+    #[serde(default = "default_bus")]
+    pub bus: Bus,
+    #[serde(flatten)]
+    pub options: SoundOptions,
+}
+
+/// The assets of a game by name, usually read from a JSON file in the
+/// resources:
+///
+/// ```json
+/// {
+///     "images": { "bird": "/images/bird.png" },
+///     "fonts": { "retro": "/fonts/retro.ttf" },
+///     "sounds": {
+///         "jump": { "path": "/sounds/jump.wav", "voices": 3 },
+///         "music": { "path": "/sounds/music.ogg", "bus": "music" }
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Manifest {
+    pub images: BTreeMap<String, String>,
+    pub fonts: BTreeMap<String, String>,
+    pub sounds: BTreeMap<String, SoundEntry>,
+}
+
+impl Manifest {
+    pub fn load(ctx: &mut Context, path: &str) -> GameResult<Self> {
+        let file = filesystem::open(ctx, path)?;
+        serde_json::from_reader(file).map_err(|e| {
+            GameError::ResourceLoadError(format!("Unable to parse asset manifest {}: {}", path, e))
+        })
+    }
+}
+
+/// Refers to an asset loaded by `Assets`; the type parameter is the kind of
+/// asset, so a font handle cannot be used to look up an image.
+pub struct Handle<A> {
+    index: usize,
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Handle<A> {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A> Clone for Handle<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A> Copy for Handle<A> {}
+
+impl<A> PartialEq for Handle<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<A> Eq for Handle<A> {}
+
+impl<A> Hash for Handle<A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<A> fmt::Debug for Handle<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Handle").field(&self.index).finish()
+    }
+}
+
+/// The assets of one kind, with the paths they were loaded from.
+pub struct Storage<A: Asset> {
+    values: Vec<A::Value>,
+    paths: Vec<String>,
+    by_path: HashMap<String, Handle<A>>,
+    by_name: HashMap<String, Handle<A>>,
+}
+
+impl<A: Asset> Default for Storage<A> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            paths: Vec::new(),
+            by_path: HashMap::new(),
+            by_name: HashMap::new(),
+        }
+    }
+}
+
+impl<A: Asset> fmt::Debug for Storage<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Storage")
+            .field("paths", &self.paths)
+            .field("names", &self.by_name)
+            .finish()
+    }
+}
+
+impl<A: Asset> Storage<A> {
+    fn insert(&mut self, path: &str, value: A::Value) -> Handle<A> {
+        let handle = Handle::new(self.values.len());
+        self.values.push(value);
+        self.paths.push(path.to_string());
+        self.by_path.insert(path.to_string(), handle);
+        handle
+    }
+}
+
+/// An asset that was loaded again from its file. Sounds keep their handle,
+/// while the entities using an image or font need the new one.
+#[derive(Clone, Debug)]
+pub enum Reloaded {
+    Image {
+        old: Arc<graphics::Image>,
+        new: Arc<graphics::Image>,
+    },
+    Font {
+        old: graphics::Font,
+        new: graphics::Font,
+    },
+    Sound(Sound),
+}
+
+/// A kind of asset stored by `Assets`, with the value its handles resolve
+/// to.
+pub trait Asset: Sized + 'static {
+    type Value: Clone;
+
+    /// How the kind is called in errors.
+    const KIND: &'static str;
+
+    fn storage(assets: &Assets) -> &Storage<Self>;
+
+    fn storage_mut(assets: &mut Assets) -> &mut Storage<Self>;
+}
+
+impl Asset for graphics::Image {
+    type Value = Arc<graphics::Image>;
+    const KIND: &'static str = "image";
+
+    fn storage(assets: &Assets) -> &Storage<Self> {
+        &assets.images
+    }
+
+    fn storage_mut(assets: &mut Assets) -> &mut Storage<Self> {
+        &mut assets.images
+    }
+}
+
+impl Asset for graphics::Font {
+    type Value = graphics::Font;
+    const KIND: &'static str = "font";
+
+    fn storage(assets: &Assets) -> &Storage<Self> {
+        &assets.fonts
+    }
+
+    fn storage_mut(assets: &mut Assets) -> &mut Storage<Self> {
+        &mut assets.fonts
+    }
+}
+
+impl Asset for Sound {
+    type Value = Sound;
+    const KIND: &'static str = "sound";
+
+    fn storage(assets: &Assets) -> &Storage<Self> {
+        &assets.sounds
+    }
+
+    fn storage_mut(assets: &mut Assets) -> &mut Storage<Self> {
+        &mut assets.sounds
+    }
+}
+
+/// Loads every asset once, however many names or loads refer to its path,
+/// and looks them up by handle or by the name the manifest gave them.
+/// Sounds are loaded into the `AudioManager`, which plays them.
+#[derive(Debug, Default)]
+pub struct Assets {
+    images: Storage<graphics::Image>,
+    fonts: Storage<graphics::Font>,
+    sounds: Storage<Sound>,
+}
+
+impl Assets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the assets of the manifest file at `path`.
+    pub fn load(ctx: &mut Context, audio: &mut AudioManager, path: &str) -> GameResult<Self> {
+        let manifest = Manifest::load(ctx, path)?;
+        let mut assets = Self::new();
+        assets.load_manifest(ctx, audio, &manifest)?;
+        Ok(assets)
+    }
+
+    /// Loads and names the assets of the manifest. Every asset is tried, so
+    /// the error lists all the assets that are missing or cannot be read.
+    pub fn load_manifest(
+        &mut self,
+        ctx: &mut Context,
+        audio: &mut AudioManager,
+        manifest: &Manifest,
+    ) -> GameResult {
+        let mut loader = AssetLoader::new(manifest);
+        while loader.load_next(ctx, self, audio) {}
+        loader.result()
+    }
+
+    fn add_name<A: Asset>(
+        &mut self,
+        name: &str,
+        path: &str,
+        handle: GameResult<Handle<A>>,
+        errors: &mut Vec<String>,
+    ) {
+        match handle {
+            Ok(handle) => {
+                A::storage_mut(self)
+                    .by_name
+                    .insert(name.to_string(), handle);
+            }
+            Err(e) => errors.push(format!("{} {} ({}): {}", A::KIND, name, path, e)),
+        }
+    }
+
+    pub fn load_image(
+        &mut self,
+        ctx: &mut Context,
+        path: &str,
+    ) -> GameResult<Handle<graphics::Image>> {
+        if let Some(handle) = self.images.by_path.get(path) {
+            return Ok(*handle);
+        }
+        let image = graphics::Image::new(ctx, path)?;
+        Ok(self.images.insert(path, Arc::new(image)))
+    }
+
+    pub fn load_font(
+        &mut self,
+        ctx: &mut Context,
+        path: &str,
+    ) -> GameResult<Handle<graphics::Font>> {
+        if let Some(handle) = self.fonts.by_path.get(path) {
+            return Ok(*handle);
+        }
+        let font = graphics::Font::new(ctx, path)?;
+        Ok(self.fonts.insert(path, font))
+    }
+
+    /// Loads a sound into the `AudioManager`. A sound that was already
+    /// loaded keeps the bus and options it was first loaded with.
+    pub fn load_sound(
+        &mut self,
+        ctx: &mut Context,
+        audio: &mut AudioManager,
+        path: &str,
+        bus: Bus,
+        options: SoundOptions,
+    ) -> GameResult<Handle<Sound>> {
+        if let Some(handle) = self.sounds.by_path.get(path) {
+            return Ok(*handle);
+        }
+        let sound = audio.load_with(ctx, path, bus, options)?;
+        Ok(self.sounds.insert(path, sound))
+    }
+
+    /// Loads the asset that was loaded from `path` again, so its handle
+    /// refers to the new version. Returns `None` if no asset was loaded
+    /// from `path`.
+    pub fn reload(
+        &mut self,
+        ctx: &mut Context,
+        audio: &mut AudioManager,
+        path: &str,
+    ) -> GameResult<Option<Reloaded>> {
+        if let Some(handle) = self.images.by_path.get(path).copied() {
+            let new = Arc::new(graphics::Image::new(ctx, path)?);
+            let old = std::mem::replace(&mut self.images.values[handle.index], new.clone());
+            return Ok(Some(Reloaded::Image { old, new }));
+        }
+        if let Some(handle) = self.fonts.by_path.get(path).copied() {
+            let new = graphics::Font::new(ctx, path)?;
+            let old = std::mem::replace(&mut self.fonts.values[handle.index], new);
+            return Ok(Some(Reloaded::Font { old, new }));
+        }
+        if let Some(handle) = self.sounds.by_path.get(path).copied() {
+            let sound = self.sounds.values[handle.index];
+            audio.reload(ctx, sound, path)?;
+            return Ok(Some(Reloaded::Sound(sound)));
+        }
+        Ok(None)
+    }
+
+    /// Returns the paths of every loaded asset.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.images
+            .paths
+            .iter()
+            .chain(&self.fonts.paths)
+            .chain(&self.sounds.paths)
+            .map(String::as_str)
+    }
+
+    /// Returns the handle of the asset the manifest named `name`.
+    pub fn handle<A: Asset>(&self, name: &str) -> Option<Handle<A>> {
+        A::storage(self).by_name.get(name).copied()
+    }
+
+    pub fn get<A: Asset>(&self, handle: Handle<A>) -> &A::Value {
+        &A::storage(self).values[handle.index]
+    }
+
+    /// Returns the path the asset was loaded from.
+    pub fn path<A: Asset>(&self, handle: Handle<A>) -> &str {
+        &A::storage(self).paths[handle.index]
+    }
+
+    /// Returns the asset the manifest named `name`.
+    pub fn named<A: Asset>(&self, name: &str) -> GameResult<A::Value> {
+        self.handle::<A>(name)
+            .map(|handle| self.get(handle).clone())
+            .ok_or_else(|| {
+                GameError::ResourceLoadError(format!(
+                    "There is no {} named {} in the asset manifest",
+                    A::KIND,
+                    name
+                ))
+            })
+    }
+
+    pub fn image(&self, name: &str) -> GameResult<Arc<graphics::Image>> {
+        self.named::<graphics::Image>(name)
+    }
+
+    pub fn font(&self, name: &str) -> GameResult<graphics::Font> {
+        self.named::<graphics::Font>(name)
+    }
+
+    pub fn sound(&self, name: &str) -> GameResult<Sound> {
+        self.named::<Sound>(name)
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use ggez::Context;
+use log::{info, warn};
+use specs::{Join, World, WorldExt};
+
+use super::{Assets, Reloaded};
+use crate::audio::AudioManager;
+use crate::particles::ParticleEmitter;
+use crate::render::{DebugOverlay, Image, Sprite, SpriteSheet, Text};
+
+/// Watches the files of the loaded assets during development and reloads
+/// the ones that change, swapping them in for the entities that use them.
+/// Files are polled, as saving a file replaces it in many editors.
+#[derive(Debug)]
+pub struct AssetWatcher {
+    /// The directory asset paths are relative to.
+    root: PathBuf,
+    interval: Duration,
+    last_check: Instant,
+    modified: HashMap<String, SystemTime>,
+}
+
+impl AssetWatcher {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self {
+            root: root.into(),
+            interval: Duration::from_millis(500),
+            last_check: Instant::now(),
+            modified: HashMap::new(),
+        }
+    }
+
+    /// Sets how often the files are checked for changes.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Reloads the `Assets` whose files changed since the last check and
+    /// returns whether any did. Call it every frame; the files are only
+    /// checked once per interval.
+    pub fn update(&mut self, ctx: &mut Context, world: &World) -> bool {
+        if self.last_check.elapsed() < self.interval {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        let mut assets = world.write_resource::<Assets>();
+        let mut audio = world.write_resource::<AudioManager>();
+        let mut reloaded = false;
+        for path in self.changed(&assets) {
+            match assets.reload(ctx, &mut audio, &path) {
+                Ok(Some(asset)) => {
+                    info!("Reloaded {}", path);
+                    swap(world, &asset);
+                    reloaded = true;
+                }
+                Ok(None) => {}
+                // The file may still be half written; it is tried again
+                // when it changes next.
+                Err(e) => warn!("Unable to reload {}: {}", path, e),
+            }
+        }
+        reloaded
+    }
+
+    /// Returns the paths of the assets whose files were modified since they
+    /// were last seen.
+    fn changed(&mut self, assets: &Assets) -> Vec<String> {
+        let mut changed = Vec::new();
+        for path in assets.paths() {
+            let file = self.root.join(path.trim_start_matches('/'));
+            let modified = match fs::metadata(&file).and_then(|metadata| metadata.modified()) {
+                Ok(modified) => modified,
+                Err(_) => continue,
+            };
+            match self.modified.insert(path.to_string(), modified) {
+                Some(previous) if previous != modified => changed.push(path.to_string()),
+                _ => {}
+            }
+        }
+        changed
+    }
+}
+
+/// Replaces the old version of a reloaded image or font in the components
+/// and the resources of the renderer that use it. The `RenderSystem` is not
+/// a resource, so its FPS font is left to the game.
+fn swap(world: &World, reloaded: &Reloaded) {
+    match reloaded {
+        Reloaded::Image { old, new } => {
+            for image in (&mut world.write_storage::<Image>()).join() {
+                if Arc::ptr_eq(&image.image, old) {
+                    image.image = new.clone();
+                }
+            }
+            for emitter in (&mut world.write_storage::<ParticleEmitter>()).join() {
+                if emitter
+                    .image
+                    .as_ref()
+                    .is_some_and(|image| Arc::ptr_eq(image, old))
+                {
+                    emitter.image = Some(new.clone());
+                }
+            }
+
+            // Sprites that shared a sheet keep sharing the new one.
+            let mut sheets: Vec<(Arc<SpriteSheet>, Arc<SpriteSheet>)> = Vec::new();
+            for sprite in (&mut world.write_storage::<Sprite>()).join() {
+                if !Arc::ptr_eq(sprite.sheet.image(), old) {
+                    continue;
+                }
+                let index = match sheets
+                    .iter()
+                    .position(|(o, _)| Arc::ptr_eq(o, &sprite.sheet))
+                {
+                    Some(index) => index,
+                    None => {
+                        let sheet = (*sprite.sheet).clone().with_image(new.clone());
+                        sheets.push((sprite.sheet.clone(), Arc::new(sheet)));
+                        sheets.len() - 1
+                    }
+                };
+                sprite.sheet = sheets[index].1.clone();
+            }
+        }
+        Reloaded::Font { old, new } => {
+            for text in (&mut world.write_storage::<Text>()).join() {
+                if text.font == *old {
+                    text.font = *new;
+                }
+            }
+            if let Some(mut overlay) = world.try_fetch_mut::<DebugOverlay>() {
+                if overlay.font == *old {
+                    overlay.font = *new;
+                }
+            }
+        }
+        Reloaded::Sound(_) => {}
+    }
+}
@@ -0,0 +1,233 @@
+use ggez::audio::{self, SoundSource};
+use ggez::{Context, GameResult};
+
+/// One of the voices a sound was loaded with, which plays independently of
+/// the others.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Voice {
+    /// The index `load` returned for the sound.
+    pub sound: usize,
+    pub voice: usize,
+}
+
+/// How a sound sounds when it is played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayParams {
+    /// From 0.0 for silent to 1.0 for full volume, on top of the volume of
+    /// the bus.
+    pub volume: f32,
+    /// From -1.0 for only the left speaker to 1.0 for only the right one.
+    pub pan: f32,
+    /// The speed the sound plays at; 2.0 sounds an octave higher.
+    pub pitch: f32,
+    /// Overrides the priority of the sound when voices get stolen.
+    pub priority: Option<u8>,
+}
+
+impl Default for PlayParams {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            pan: 0.0,
+            pitch: 1.0,
+            priority: None,
+        }
+    }
+}
+
+impl PlayParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_pan(mut self, pan: f32) -> Self {
+        self.pan = pan.clamp(-1.0, 1.0);
+        self
+    }
+
+    pub fn with_pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+}
+
+/// Plays the sounds of the `AudioManager`.
+pub trait AudioBackend: Send + Sync {
+    /// Loads the sound file at `path` with the given number of voices and
+    /// returns its index.
+    fn load(&mut self, ctx: &mut Context, path: &str, voices: usize) -> GameResult<usize>;
+
+    /// Replaces the sound at `sound` with the file at `path`, keeping its
+    /// index and number of voices.
+    fn reload(&mut self, ctx: &mut Context, sound: usize, path: &str) -> GameResult;
+
+    /// Plays the voice from its start. The volume of `params` includes the
+    /// volume of the bus.
+    fn play(&mut self, voice: Voice, params: &PlayParams) -> GameResult;
+
+    /// Changes the volume of the voice, also while it is playing.
+    fn set_volume(&mut self, voice: Voice, volume: f32);
+
+    /// Makes the voice start over when it ends, without a gap.
+    fn set_looping(&mut self, voice: Voice, looping: bool);
+
+    fn stop(&mut self, voice: Voice);
+
+    fn pause(&mut self, voice: Voice);
+
+    fn resume(&mut self, voice: Voice);
+
+    fn is_playing(&self, voice: Voice) -> bool;
+}
+
+// The ears sounds are panned between, with a sound at the pan along the x
+// axis in between them.
+const LEFT_EAR: [f32; 3] = [-1.0, 0.0, 0.0];
+const RIGHT_EAR: [f32; 3] = [1.0, 0.0, 0.0];
+
+/// Plays sounds through the audio device of the ggez context. The voices of
+/// a sound share its decoded data.
+#[derive(Debug, Default)]
+pub struct GgezAudio {
+    sources: Vec<Vec<audio::SpatialSource>>,
+}
+
+impl GgezAudio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn load_voices(
+        ctx: &mut Context,
+        path: &str,
+        voices: usize,
+    ) -> GameResult<Vec<audio::SpatialSource>> {
+        let data = audio::SoundData::new(ctx, path)?;
+        (0..voices.max(1))
+            .map(|_| {
+                let mut source = audio::SpatialSource::from_data(ctx, data.clone())?;
+                source.set_ears(LEFT_EAR, RIGHT_EAR);
+                Ok(source)
+            })
+            .collect()
+    }
+
+    fn source(&mut self, voice: Voice) -> Option<&mut audio::SpatialSource> {
+        self.sources
+            .get_mut(voice.sound)
+            .and_then(|voices| voices.get_mut(voice.voice))
+    }
+}
+
+impl AudioBackend for GgezAudio {
+    fn load(&mut self, ctx: &mut Context, path: &str, voices: usize) -> GameResult<usize> {
+        let voices = Self::load_voices(ctx, path, voices)?;
+        self.sources.push(voices);
+        Ok(self.sources.len() - 1)
+    }
+
+    fn reload(&mut self, ctx: &mut Context, sound: usize, path: &str) -> GameResult {
+        if let Some(voices) = self.sources.get(sound).map(Vec::len) {
+            self.sources[sound] = Self::load_voices(ctx, path, voices)?;
+        }
+        Ok(())
+    }
+
+    fn play(&mut self, voice: Voice, params: &PlayParams) -> GameResult {
+        if let Some(source) = self.source(voice) {
+            source.set_volume(params.volume);
+            source.set_pitch(params.pitch);
+            source.set_position([params.pan, 0.0, 0.0]);
+            source.play()?;
+        }
+        Ok(())
+    }
+
+    fn set_volume(&mut self, voice: Voice, volume: f32) {
+        if let Some(source) = self.source(voice) {
+            source.set_volume(volume);
+        }
+    }
+
+    fn set_looping(&mut self, voice: Voice, looping: bool) {
+        if let Some(source) = self.source(voice) {
+            source.set_repeat(looping);
+        }
+    }
+
+    fn stop(&mut self, voice: Voice) {
+        if let Some(source) = self.source(voice) {
+            source.stop();
+        }
+    }
+
+    fn pause(&mut self, voice: Voice) {
+        if let Some(source) = self.source(voice) {
+            source.pause();
+        }
+    }
+
+    fn resume(&mut self, voice: Voice) {
+        if let Some(source) = self.source(voice) {
+            source.resume();
+        }
+    }
+
+    fn is_playing(&self, voice: Voice) -> bool {
+        self.sources
+            .get(voice.sound)
+            .and_then(|voices| voices.get(voice.voice))
+            .is_some_and(|source| source.playing())
+    }
+}
+
+/// Loads and plays nothing, for when there is no audio device.
+#[derive(Debug, Default)]
+pub struct NullAudio {
+    loaded: usize,
+}
+
+impl NullAudio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AudioBackend for NullAudio {
+    fn load(&mut self, _ctx: &mut Context, _path: &str, _voices: usize) -> GameResult<usize> {
+        self.loaded += 1;
+        Ok(self.loaded - 1)
+    }
+
+    fn reload(&mut self, _ctx: &mut Context, _sound: usize, _path: &str) -> GameResult {
+        Ok(())
+    }
+
+    fn play(&mut self, _voice: Voice, _params: &PlayParams) -> GameResult {
+        Ok(())
+    }
+
+    fn set_volume(&mut self, _voice: Voice, _volume: f32) {}
+
+    fn set_looping(&mut self, _voice: Voice, _looping: bool) {}
+
+    fn stop(&mut self, _voice: Voice) {}
+
+    fn pause(&mut self, _voice: Voice) {}
+
+    fn resume(&mut self, _voice: Voice) {}
+
+    fn is_playing(&self, _voice: Voice) -> bool {
+        false
+    }
+}
@@ -0,0 +1,357 @@
+//! An audio manager resource that plays sounds and music on volume
+//! controlled buses.
+use ggez::{Context, GameResult};
+use log::warn;
+use serde_derive::Deserialize;
+use specs::{Read, System, Write, WriteExpect};
+
+use crate::render::Position;
+use crate::types::DeltaTime;
+use music::Track;
+use pool::SoundPool;
+
+pub mod backend;
+mod music;
+mod pool;
+mod queue;
+pub mod settings;
+
+pub use backend::*;
+pub use pool::SoundOptions;
+pub use queue::{SoundEvent, SoundQueue};
+pub use settings::*;
+
+/// A group of sounds that share a volume and mute setting. Every bus is
+/// also controlled by the master bus.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Bus {
+    Master,
+    Music,
+    Sfx,
+}
+
+/// A sound loaded by the `AudioManager`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Sound {
+    index: usize,
+    bus: Bus,
+}
+
+impl Sound {
+    /// Creates a handle that no `AudioManager` loaded, which plays nothing.
+    /// Systems that only queue sounds can be tested with it.
+    pub fn unloaded(index: usize, bus: Bus) -> Self {
+        Self { index, bus }
+    }
+
+    pub fn bus(&self) -> Bus {
+        self.bus
+    }
+}
+
+/// Loads and plays sounds, applying the volume and mute settings of their
+/// bus. Failing to play a sound is logged rather than returned, as a game
+/// should keep running without it.
+///
+/// Every sound has a pool of voices, so a sound can play over itself; its
+/// `SoundOptions` limit how often and how many times at once it plays.
+///
+/// One music track loops at a time; switching tracks crossfades between
+/// them, which the `AudioSystem` advances every update.
+pub struct AudioManager {
+    backend: Box<dyn AudioBackend>,
+    settings: AudioSettings,
+    sounds: Vec<SoundPool>,
+    music: Vec<Track>,
+    music_paused: bool,
+    /// The time the manager was updated for, in seconds.
+    time: f32,
+    /// How many times a sound was played, to order the voices.
+    plays: u64,
+}
+
+impl std::fmt::Debug for AudioManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AudioManager")
+            .field("settings", &self.settings)
+            .field("sounds", &self.sounds.len())
+            .field("music", &self.music)
+            .field("music_paused", &self.music_paused)
+            .finish()
+    }
+}
+
+impl AudioManager {
+    /// Creates a manager that plays through ggez, or one that stays silent
+    /// if the context was built without the `audio` module.
+    pub fn new(audio: bool) -> Self {
+        if audio {
+            Self::with_backend(Box::new(GgezAudio::new()))
+        } else {
+            Self::with_backend(Box::new(NullAudio::new()))
+        }
+    }
+
+    pub fn with_backend(backend: Box<dyn AudioBackend>) -> Self {
+        Self {
+            backend,
+            settings: AudioSettings::default(),
+            sounds: Vec::new(),
+            music: Vec::new(),
+            music_paused: false,
+            time: 0.0,
+            plays: 0,
+        }
+    }
+
+    pub fn with_settings(mut self, settings: AudioSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Loads the sound file at `path` to be played on `bus`, with a single
+    /// voice.
+    pub fn load(&mut self, ctx: &mut Context, path: &str, bus: Bus) -> GameResult<Sound> {
+        self.load_with(ctx, path, bus, SoundOptions::default())
+    }
+
+    /// Loads the sound file at `path` to be played on `bus` as `options`
+    /// allow.
+    pub fn load_with(
+        &mut self,
+        ctx: &mut Context,
+        path: &str,
+        bus: Bus,
+        options: SoundOptions,
+    ) -> GameResult<Sound> {
+        let id = self.backend.load(ctx, path, options.voices)?;
+        let sound = Sound {
+            index: self.sounds.len(),
+            bus,
+        };
+        self.sounds.push(SoundPool::new(id, sound, options));
+        Ok(sound)
+    }
+
+    /// Replaces `sound` with the file at `path`, keeping its handle. Music
+    /// that was playing it starts over with the new file.
+    pub fn reload(&mut self, ctx: &mut Context, sound: Sound, path: &str) -> GameResult {
+        let pool = match self.sounds.get(sound.index) {
+            Some(pool) => pool,
+            None => return Ok(()),
+        };
+        for voice in pool.voices() {
+            self.backend.stop(voice);
+        }
+        self.backend.reload(ctx, pool.id, path)?;
+
+        for track in self.music.iter().filter(|track| track.sound == sound) {
+            self.backend.set_looping(track.voice, true);
+            self.backend
+                .play(track.voice, &PlayParams::new().with_volume(0.0))?;
+            if self.music_paused {
+                self.backend.pause(track.voice);
+            }
+        }
+        self.apply_music_volumes();
+        Ok(())
+    }
+
+    /// Plays the sound on a free voice. If all its voices are busy, the
+    /// oldest one with the same or a lower priority is stolen.
+    pub fn play(&mut self, sound: Sound) {
+        self.play_with(sound, PlayParams::default());
+    }
+
+    /// Plays the sound like `play`, with its own volume, pan and pitch.
+    pub fn play_with(&mut self, sound: Sound, params: PlayParams) {
+        let pool = match self.sounds.get_mut(sound.index) {
+            Some(pool) => pool,
+            None => return,
+        };
+        let priority = params.priority.unwrap_or(pool.options.priority);
+        let voice = match pool.pick_voice(&*self.backend, self.time, priority, self.plays) {
+            Some(voice) => voice,
+            None => return,
+        };
+        self.plays += 1;
+
+        let params = PlayParams {
+            volume: params.volume * self.settings.effective_volume(sound.bus),
+            ..params
+        };
+        self.backend.stop(voice);
+        if let Err(e) = self.backend.play(voice, &params) {
+            warn!("Unable to play sound: {}", e);
+        }
+    }
+
+    /// Loops `sound` as the music, crossfading from the current music over
+    /// `fade` seconds. The track that is already playing just keeps going.
+    pub fn play_music(&mut self, sound: Sound, fade: f32) {
+        if self.current_music() == Some(sound) {
+            return;
+        }
+
+        for track in &mut self.music {
+            track.fade_to(0.0, fade);
+        }
+        match self.music.iter_mut().find(|track| track.sound == sound) {
+            // A track that is still fading out fades back in from there.
+            Some(track) => track.fade_to(1.0, fade),
+            None => {
+                let voice = match self.sounds.get(sound.index) {
+                    Some(pool) => pool.voice(0),
+                    None => return,
+                };
+                self.backend.set_looping(voice, true);
+                let params = PlayParams::new().with_volume(0.0);
+                if let Err(e) = self.backend.play(voice, &params) {
+                    warn!("Unable to play music: {}", e);
+                    return;
+                }
+                if self.music_paused {
+                    self.backend.pause(voice);
+                }
+
+                let mut track = Track::new(sound, voice);
+                track.fade_to(1.0, fade);
+                self.music.push(track);
+            }
+        }
+        self.apply_music_volumes();
+    }
+
+    /// Fades out the music over `fade` seconds.
+    pub fn stop_music(&mut self, fade: f32) {
+        for track in &mut self.music {
+            track.fade_to(0.0, fade);
+        }
+        self.apply_music_volumes();
+    }
+
+    /// Returns the music track that is playing or fading in.
+    pub fn current_music(&self) -> Option<Sound> {
+        self.music
+            .iter()
+            .find(|track| track.target > 0.0)
+            .map(|track| track.sound)
+    }
+
+    /// Pauses the music, including any crossfade, until it is resumed.
+    pub fn pause_music(&mut self) {
+        self.music_paused = true;
+        for track in &self.music {
+            self.backend.pause(track.voice);
+        }
+    }
+
+    pub fn resume_music(&mut self) {
+        self.music_paused = false;
+        for track in &self.music {
+            self.backend.resume(track.voice);
+        }
+    }
+
+    pub fn is_music_paused(&self) -> bool {
+        self.music_paused
+    }
+
+    /// Advances the cooldowns and the music fades, and stops the tracks
+    /// that faded out.
+    pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+        if self.music_paused {
+            return;
+        }
+
+        for track in &mut self.music {
+            track.update(delta);
+        }
+        let backend = &mut self.backend;
+        self.music.retain(|track| {
+            if track.is_silent() {
+                backend.stop(track.voice);
+            }
+            !track.is_silent()
+        });
+        self.apply_music_volumes();
+    }
+
+    pub fn settings(&self) -> &AudioSettings {
+        &self.settings
+    }
+
+    pub fn volume(&self, bus: Bus) -> f32 {
+        self.settings.bus(bus).volume
+    }
+
+    pub fn set_volume(&mut self, bus: Bus, volume: f32) {
+        self.settings.bus_mut(bus).volume = volume.clamp(0.0, 1.0);
+        self.apply_volumes();
+    }
+
+    pub fn is_muted(&self, bus: Bus) -> bool {
+        self.settings.bus(bus).muted
+    }
+
+    pub fn set_muted(&mut self, bus: Bus, muted: bool) {
+        self.settings.bus_mut(bus).muted = muted;
+        self.apply_volumes();
+    }
+
+    pub fn toggle_muted(&mut self, bus: Bus) {
+        self.set_muted(bus, !self.is_muted(bus));
+    }
+
+    /// Updates the volume of every sound, so sounds that are still playing
+    /// follow the settings.
+    fn apply_volumes(&mut self) {
+        for pool in &self.sounds {
+            let volume = self.settings.effective_volume(pool.sound.bus);
+            for voice in pool.voices() {
+                let level = self
+                    .music
+                    .iter()
+                    .find(|track| track.voice == voice)
+                    .map_or(1.0, |track| track.level);
+                self.backend.set_volume(voice, volume * level);
+            }
+        }
+    }
+
+    fn apply_music_volumes(&mut self) {
+        for track in &self.music {
+            self.backend.set_volume(
+                track.voice,
+                self.settings.effective_volume(track.sound.bus) * track.level,
+            );
+        }
+    }
+}
+
+/// Returns the pan of a sound made at `pos` on a screen `width` wide, from
+/// -1.0 at its left edge to 1.0 at its right edge.
+pub fn pan_from_position(pos: &Position, width: f32) -> f32 {
+    (pos.x / width * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+/// Advances the music of the `AudioManager` and plays the sounds in the
+/// `SoundQueue`; add it to the fixed update after the systems that queue
+/// sounds.
+pub struct AudioSystem;
+impl<'s> System<'s> for AudioSystem {
+    type SystemData = (
+        WriteExpect<'s, AudioManager>,
+        Write<'s, SoundQueue>,
+        Read<'s, DeltaTime>,
+    );
+
+    fn run(&mut self, (mut audio, mut queue, dt): Self::SystemData) {
+        audio.update(dt.delta);
+        for event in queue.drain() {
+            audio.play_with(event.sound, event.params);
+        }
+    }
+}
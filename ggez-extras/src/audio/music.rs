@@ -0,0 +1,51 @@
+use super::backend::Voice;
+use super::Sound;
+
+/// A looping music track that is playing, fading in or fading out.
+#[derive(Clone, Debug)]
+pub(crate) struct Track {
+    pub sound: Sound,
+    pub voice: Voice,
+    /// How far the track is faded in, from 0.0 for silent to 1.0.
+    pub level: f32,
+    pub target: f32,
+    /// How much the level changes per second.
+    rate: f32,
+}
+
+impl Track {
+    pub fn new(sound: Sound, voice: Voice) -> Self {
+        Self {
+            sound,
+            voice,
+            level: 0.0,
+            target: 0.0,
+            rate: 0.0,
+        }
+    }
+
+    /// Fades towards `target` over `time` seconds, or jumps to it right away
+    /// if `time` is not positive.
+    pub fn fade_to(&mut self, target: f32, time: f32) {
+        self.target = target;
+        if time > 0.0 {
+            self.rate = 1.0 / time;
+        } else {
+            self.level = target;
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        let step = self.rate * delta;
+        if self.level < self.target {
+            self.level = (self.level + step).min(self.target);
+        } else {
+            self.level = (self.level - step).max(self.target);
+        }
+    }
+
+    /// Returns whether the track faded out completely.
+    pub fn is_silent(&self) -> bool {
+        self.target <= 0.0 && self.level <= 0.0
+    }
+}
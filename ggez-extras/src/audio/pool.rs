@@ -0,0 +1,241 @@
+use super::backend::{AudioBackend, Voice};
+use serde::Deserializer;
+use serde_derive::Deserialize;
+
+use super::Sound;
+
+/// How a sound is played when it is triggered often.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SoundOptions {
+    /// How many copies of the sound can play at the same time, at least one.
+    #[serde(deserialize_with = "at_least_one")]
+    pub voices: usize,
+    /// The minimum time between two plays, in seconds.
+    pub cooldown: f32,
+    /// When all voices are busy, a play steals the oldest voice started
+    /// with the same or a lower priority, or is dropped if there is none.
+    pub priority: u8,
+}
+
+impl Default for SoundOptions {
+    fn default() -> Self {
+        Self {
+            voices: 1,
+            cooldown: 0.0,
+            priority: 0,
+        }
+    }
+}
+
+impl SoundOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_voices(mut self, voices: usize) -> Self {
+        self.voices = voices.max(1);
+        self
+    }
+
+    pub fn with_cooldown(mut self, cooldown: f32) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+fn at_least_one<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    let voices: usize = serde::Deserialize::deserialize(deserializer)?;
+    Ok(voices.max(1))
+}
+
+#[derive(Clone, Debug, Default)]
+struct VoiceState {
+    priority: u8,
+    /// Which play started the voice, to find the oldest one.
+    started: u64,
+}
+
+/// The voices of a loaded sound.
+#[derive(Clone, Debug)]
+pub(crate) struct SoundPool {
+    /// The index of the sound in the backend.
+    pub id: usize,
+    pub sound: Sound,
+    pub options: SoundOptions,
+    voices: Vec<VoiceState>,
+    last_played: Option<f32>,
+}
+
+impl SoundPool {
+    /// Creates the pool with the voices of `options`, but at least one, as
+    /// the backend loads at least one.
+    pub fn new(id: usize, sound: Sound, options: SoundOptions) -> Self {
+        let options = SoundOptions {
+            voices: options.voices.max(1),
+            ..options
+        };
+        Self {
+            id,
+            sound,
+            voices: vec![VoiceState::default(); options.voices],
+            options,
+            last_played: None,
+        }
+    }
+
+    pub fn voice(&self, voice: usize) -> Voice {
+        Voice {
+            sound: self.id,
+            voice,
+        }
+    }
+
+    pub fn voices(&self) -> impl Iterator<Item = Voice> + '_ {
+        (0..self.voices.len()).map(move |voice| self.voice(voice))
+    }
+
+    /// Picks the voice to play the sound on at `time`: a free voice, or the
+    /// oldest voice with the same or a lower priority to steal. Returns
+    /// `None` while the sound is cooling down or when only voices with a
+    /// higher priority are playing.
+    pub fn pick_voice(
+        &mut self,
+        backend: &dyn AudioBackend,
+        time: f32,
+        priority: u8,
+        play: u64,
+    ) -> Option<Voice> {
+        if let Some(last_played) = self.last_played {
+            if time - last_played < self.options.cooldown {
+                return None;
+            }
+        }
+
+        let free = (0..self.voices.len()).find(|voice| !backend.is_playing(self.voice(*voice)));
+        let index = match free {
+            Some(index) => index,
+            None => {
+                let (index, _) = self
+                    .voices
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, state)| state.priority <= priority)
+                    .min_by_key(|(_, state)| state.started)?;
+                index
+            }
+        };
+
+        self.voices[index] = VoiceState {
+            priority,
+            started: play,
+        };
+        self.last_played = Some(time);
+        Some(self.voice(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ggez::{Context, GameResult};
+
+    use super::super::backend::{NullAudio, PlayParams};
+    use super::super::Bus;
+    use super::*;
+
+    /// A backend on which every voice is busy, so plays have to steal one.
+    struct BusyAudio;
+
+    impl AudioBackend for BusyAudio {
+        fn load(&mut self, _ctx: &mut Context, _path: &str, _voices: usize) -> GameResult<usize> {
+            Ok(0)
+        }
+
+        fn reload(&mut self, _ctx: &mut Context, _sound: usize, _path: &str) -> GameResult {
+            Ok(())
+        }
+
+        fn play(&mut self, _voice: Voice, _params: &PlayParams) -> GameResult {
+            Ok(())
+        }
+
+        fn set_volume(&mut self, _voice: Voice, _volume: f32) {}
+
+        fn set_looping(&mut self, _voice: Voice, _looping: bool) {}
+
+        fn stop(&mut self, _voice: Voice) {}
+
+        fn pause(&mut self, _voice: Voice) {}
+
+        fn resume(&mut self, _voice: Voice) {}
+
+        fn is_playing(&self, _voice: Voice) -> bool {
+            true
+        }
+    }
+
+    fn sound() -> Sound {
+        Sound {
+            index: 0,
+            bus: Bus::Sfx,
+        }
+    }
+
+    #[test]
+    fn deserializes_at_least_one_voice() {
+        let options: SoundOptions = serde_json::from_str(r#"{ "voices": 0 }"#).unwrap();
+        assert_eq!(options.voices, 1);
+        let options: SoundOptions = serde_json::from_str(r#"{ "voices": 3 }"#).unwrap();
+        assert_eq!(options.voices, 3);
+        let options: SoundOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options.voices, 1);
+    }
+
+    #[test]
+    fn plays_a_pool_created_without_voices() {
+        let options = SoundOptions {
+            voices: 0,
+            ..Default::default()
+        };
+        let mut pool = SoundPool::new(0, sound(), options);
+        assert_eq!(pool.options.voices, 1);
+        assert_eq!(pool.voices().count(), 1);
+        assert!(pool.pick_voice(&NullAudio::new(), 0.0, 0, 0).is_some());
+    }
+
+    /// Returns a pool whose two voices were started by plays 1 and 2, with
+    /// the given priorities.
+    fn busy_pool(first: u8, second: u8) -> SoundPool {
+        let mut pool = SoundPool::new(0, sound(), SoundOptions::new().with_voices(2));
+        pool.pick_voice(&NullAudio::new(), 0.0, first, 1);
+        pool.pick_voice(&BusyAudio, 0.0, second, 2);
+        pool
+    }
+
+    #[test]
+    fn steals_the_oldest_voice_with_a_lower_priority() {
+        let mut pool = busy_pool(1, 5);
+
+        assert_eq!(pool.pick_voice(&BusyAudio, 0.0, 5, 3), Some(pool.voice(0)));
+    }
+
+    #[test]
+    fn steals_the_oldest_voice_rather_than_the_lowest_priority() {
+        let mut pool = busy_pool(5, 1);
+
+        assert_eq!(pool.pick_voice(&BusyAudio, 0.0, 5, 3), Some(pool.voice(0)));
+    }
+
+    #[test]
+    fn skips_voices_with_a_higher_priority() {
+        let mut pool = busy_pool(5, 1);
+
+        assert_eq!(pool.pick_voice(&BusyAudio, 0.0, 3, 3), Some(pool.voice(1)));
+        assert_eq!(pool.pick_voice(&BusyAudio, 0.0, 0, 4), None);
+    }
+}
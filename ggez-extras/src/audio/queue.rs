@@ -0,0 +1,40 @@
+use super::backend::PlayParams;
+use super::Sound;
+
+/// A sound a system asked to be played.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoundEvent {
+    pub sound: Sound,
+    pub params: PlayParams,
+}
+
+/// The sounds systems want to play during an update. Systems only write to
+/// the queue, so they don't need the `AudioManager` or an audio device; the
+/// `AudioSystem` plays and clears the queue.
+#[derive(Clone, Debug, Default)]
+pub struct SoundQueue {
+    events: Vec<SoundEvent>,
+}
+
+impl SoundQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn play(&mut self, sound: Sound) {
+        self.play_with(sound, PlayParams::default());
+    }
+
+    pub fn play_with(&mut self, sound: Sound, params: PlayParams) {
+        self.events.push(SoundEvent { sound, params });
+    }
+
+    /// Returns the sounds queued since the queue was last drained.
+    pub fn events(&self) -> &[SoundEvent] {
+        &self.events
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = SoundEvent> + '_ {
+        self.events.drain(..)
+    }
+}
@@ -0,0 +1,63 @@
+use serde_derive::{Deserialize, Serialize};
+
+use super::Bus;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BusSettings {
+    /// From 0.0 for silent to 1.0 for full volume.
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Default for BusSettings {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            muted: false,
+        }
+    }
+}
+
+/// The volume and mute state of every bus, which is kept between runs in
+/// the `Settings`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: BusSettings,
+    pub music: BusSettings,
+    pub sfx: BusSettings,
+}
+
+impl AudioSettings {
+    pub fn bus(&self, bus: Bus) -> &BusSettings {
+        match bus {
+            Bus::Master => &self.master,
+            Bus::Music => &self.music,
+            Bus::Sfx => &self.sfx,
+        }
+    }
+
+    pub fn bus_mut(&mut self, bus: Bus) -> &mut BusSettings {
+        match bus {
+            Bus::Master => &mut self.master,
+            Bus::Music => &mut self.music,
+            Bus::Sfx => &mut self.sfx,
+        }
+    }
+
+    /// Returns the volume sounds on `bus` play at, taking the master bus
+    /// and muting into account.
+    pub fn effective_volume(&self, bus: Bus) -> f32 {
+        let volume = |settings: &BusSettings| {
+            if settings.muted {
+                0.0
+            } else {
+                settings.volume
+            }
+        };
+        match bus {
+            Bus::Master => volume(&self.master),
+            _ => volume(&self.master) * volume(self.bus(bus)),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! A 2D camera resource that the `RenderSystem` draws the world through.
+use ggez::{graphics, mint};
+use rand::Rng;
+use specs::{Entity, Read, ReadStorage, System, WriteExpect};
+
+use crate::render::{Position, Size};
+use crate::types::DeltaTime;
+
+/// Looks at a point of the world with a zoom and rotation. It can follow an
+/// entity, stay within world bounds and shake based on accumulated trauma.
+#[derive(Clone, Debug)]
+pub struct Camera {
+    /// The world position shown at the center of the screen.
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+    pub rotation: f32,
+    /// The entity to follow, centered on its `Position` and `Size`.
+    pub target: Option<Entity>,
+    /// How fast the camera catches up with its target; `None` snaps to it.
+    pub follow_speed: Option<f32>,
+    /// The area of the world the camera is not allowed to look past.
+    pub bounds: Option<graphics::Rect>,
+    /// The maximum shake offset in world units and rotation in radians.
+    pub max_shake_offset: f32,
+    pub max_shake_angle: f32,
+    /// How much trauma is removed per second.
+    pub trauma_decay: f32,
+    trauma: f32,
+    viewport_w: f32,
+    viewport_h: f32,
+    shake: (f32, f32, f32),
+}
+
+impl Camera {
+    /// Creates a camera for a screen of the given virtual size that shows
+    /// the world exactly as it would be drawn without a camera.
+    pub fn new(viewport_w: f32, viewport_h: f32) -> Self {
+        Self {
+            x: viewport_w / 2.0,
+            y: viewport_h / 2.0,
+            zoom: 1.0,
+            rotation: 0.0,
+            target: None,
+            follow_speed: None,
+            bounds: None,
+            max_shake_offset: 8.0,
+            max_shake_angle: 0.05,
+            trauma_decay: 1.5,
+            trauma: 0.0,
+            viewport_w,
+            viewport_h,
+            shake: (0.0, 0.0, 0.0),
+        }
+    }
+
+    pub fn follow(mut self, target: Entity, follow_speed: Option<f32>) -> Self {
+        self.target = Some(target);
+        self.follow_speed = follow_speed;
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: graphics::Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Adds trauma in the range [0, 1]; the shake grows with its square.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Moves the camera towards `(x, y)` and decays the shake.
+    pub fn update(&mut self, target: Option<(f32, f32)>, delta: f32) {
+        if let Some((x, y)) = target {
+            let t = match self.follow_speed {
+                Some(speed) => 1.0 - (-speed * delta).exp(),
+                None => 1.0,
+            };
+            self.x += (x - self.x) * t;
+            self.y += (y - self.y) * t;
+        }
+
+        if let Some(bounds) = self.bounds {
+            let half_w = self.viewport_w / (2.0 * self.zoom);
+            let half_h = self.viewport_h / (2.0 * self.zoom);
+            self.x = clamp_or_center(self.x, bounds.x + half_w, bounds.x + bounds.w - half_w);
+            self.y = clamp_or_center(self.y, bounds.y + half_h, bounds.y + bounds.h - half_h);
+        }
+
+        self.trauma = (self.trauma - self.trauma_decay * delta).max(0.0);
+        let shake = self.trauma * self.trauma;
+        if shake > 0.0 {
+            let mut rng = rand::thread_rng();
+            self.shake = (
+                self.max_shake_offset * shake * rng.gen_range(-1.0, 1.0),
+                self.max_shake_offset * shake * rng.gen_range(-1.0, 1.0),
+                self.max_shake_angle * shake * rng.gen_range(-1.0, 1.0),
+            );
+        } else {
+            self.shake = (0.0, 0.0, 0.0);
+        }
+    }
+
+    /// Returns the matrix that transforms world coordinates to screen
+    /// coordinates, including the current shake.
+    pub fn transform(&self) -> mint::ColumnMatrix4<f32> {
+        self.parallax_transform(1.0)
+    }
+
+    /// Like `transform`, but only follows `factor` of the camera movement
+    /// away from its starting point, for layers that should look nearer or
+    /// further away than the rest of the world.
+    pub fn parallax_transform(&self, factor: f32) -> mint::ColumnMatrix4<f32> {
+        let (shake_x, shake_y, shake_angle) = self.shake;
+        let angle = -(self.rotation + shake_angle);
+        let (sin, cos) = angle.sin_cos();
+        let a = self.zoom * cos;
+        let b = -self.zoom * sin;
+        let c = self.zoom * sin;
+        let d = self.zoom * cos;
+
+        let x = self.viewport_w / 2.0 + (self.x - self.viewport_w / 2.0) * factor + shake_x;
+        let y = self.viewport_h / 2.0 + (self.y - self.viewport_h / 2.0) * factor + shake_y;
+        let tx = self.viewport_w / 2.0 - (a * x + b * y);
+        let ty = self.viewport_h / 2.0 - (c * x + d * y);
+
+        mint::ColumnMatrix4 {
+            x: mint::Vector4 {
+                x: a,
+                y: c,
+                z: 0.0,
+                w: 0.0,
+            },
+            y: mint::Vector4 {
+                x: b,
+                y: d,
+                z: 0.0,
+                w: 0.0,
+            },
+            z: mint::Vector4 {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+                w: 0.0,
+            },
+            w: mint::Vector4 {
+                x: tx,
+                y: ty,
+                z: 0.0,
+                w: 1.0,
+            },
+        }
+    }
+}
+
+/// Clamps `value`, or centers it when the range is empty because the view
+/// is larger than the bounds.
+fn clamp_or_center(value: f32, min: f32, max: f32) -> f32 {
+    if min > max {
+        (min + max) / 2.0
+    } else {
+        value.clamp(min, max)
+    }
+}
+
+/// Updates the `Camera` resource: follows its target and decays the shake.
+pub struct CameraSystem;
+impl<'s> System<'s> for CameraSystem {
+    type SystemData = (
+        WriteExpect<'s, Camera>,
+        ReadStorage<'s, Position>,
+        ReadStorage<'s, Size>,
+        Read<'s, DeltaTime>,
+    );
+
+    fn run(&mut self, (mut camera, positions, sizes, dt): Self::SystemData) {
+        let target = camera.target.and_then(|e| {
+            positions.get(e).map(|pos| match sizes.get(e) {
+                Some(size) => (pos.x + size.w / 2.0, pos.y + size.h / 2.0),
+                None => (pos.x, pos.y),
+            })
+        });
+        camera.update(target, dt.delta);
+    }
+}
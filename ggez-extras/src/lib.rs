@@ -1,3 +1,12 @@
+pub mod animation;
+pub mod assets;
+pub mod audio;
+pub mod camera;
 pub mod input;
 pub mod logging;
+pub mod particles;
+pub mod render;
+pub mod resources;
+pub mod settings;
+pub mod types;
 pub mod util;
@@ -0,0 +1,223 @@
+//! Particle effects emitted from entities and drawn by the `RenderSystem`.
+use ggez::{graphics, mint};
+use rand::Rng;
+use specs::{Component, Join, Read, ReadStorage, System, VecStorage, WriteStorage};
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::render::Position;
+use crate::types::DeltaTime;
+
+/// A single particle, in world coordinates.
+#[derive(Clone, Debug)]
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// Returns how far the particle is through its life, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.lifetime > 0.0 {
+            (self.age / self.lifetime).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Spawns particles at the entity `Position`, continuously at `rate` while
+/// `emitting` and in bursts. Every particle gets a random lifetime, speed
+/// and direction from the ranges, and fades from the start to the end
+/// colour and size over its life. Particles without an image are drawn as
+/// squares.
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct ParticleEmitter {
+    /// Particles spawned per second while emitting.
+    pub rate: f32,
+    pub emitting: bool,
+    /// Lifetime in seconds.
+    pub lifetime: Range<f32>,
+    /// Speed in world units per second.
+    pub speed: Range<f32>,
+    /// Direction in radians, 0 pointing right.
+    pub angle: Range<f32>,
+    pub gravity: mint::Vector2<f32>,
+    /// Where particles spawn, relative to the entity position.
+    pub offset: mint::Vector2<f32>,
+    pub colors: (graphics::Color, graphics::Color),
+    pub sizes: (f32, f32),
+    pub image: Option<Arc<graphics::Image>>,
+    pub max_particles: usize,
+    particles: Vec<Particle>,
+    burst: usize,
+    spawn_timer: f32,
+}
+
+impl ParticleEmitter {
+    /// Creates an emitter that only spawns particles in bursts.
+    pub fn new(lifetime: Range<f32>) -> Self {
+        Self {
+            rate: 0.0,
+            emitting: false,
+            lifetime,
+            speed: 0.0..0.0,
+            angle: 0.0..std::f32::consts::PI * 2.0,
+            gravity: mint::Vector2 { x: 0.0, y: 0.0 },
+            offset: mint::Vector2 { x: 0.0, y: 0.0 },
+            colors: (graphics::WHITE, graphics::WHITE),
+            sizes: (1.0, 1.0),
+            image: None,
+            max_particles: 256,
+            particles: Vec::new(),
+            burst: 0,
+            spawn_timer: 0.0,
+        }
+    }
+
+    /// Spawns `rate` particles per second until `emitting` is unset.
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        self.rate = rate;
+        self.emitting = true;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: Range<f32>) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_angle(mut self, angle: Range<f32>) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    pub fn with_gravity(mut self, x: f32, y: f32) -> Self {
+        self.gravity = mint::Vector2 { x, y };
+        self
+    }
+
+    pub fn with_offset(mut self, x: f32, y: f32) -> Self {
+        self.offset = mint::Vector2 { x, y };
+        self
+    }
+
+    pub fn with_colors(mut self, start: graphics::Color, end: graphics::Color) -> Self {
+        self.colors = (start, end);
+        self
+    }
+
+    pub fn with_sizes(mut self, start: f32, end: f32) -> Self {
+        self.sizes = (start, end);
+        self
+    }
+
+    pub fn with_image(mut self, image: Arc<graphics::Image>) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    pub fn with_max_particles(mut self, max_particles: usize) -> Self {
+        self.max_particles = max_particles;
+        self
+    }
+
+    /// Spawns `count` particles on the next update.
+    pub fn burst(&mut self, count: usize) {
+        self.burst += count;
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Returns the colour of a particle at its point in life.
+    pub fn color(&self, particle: &Particle) -> graphics::Color {
+        let t = particle.progress();
+        let (start, end) = self.colors;
+        graphics::Color::new(
+            lerp(start.r, end.r, t),
+            lerp(start.g, end.g, t),
+            lerp(start.b, end.b, t),
+            lerp(start.a, end.a, t),
+        )
+    }
+
+    /// Returns the size of a particle at its point in life.
+    pub fn size(&self, particle: &Particle) -> f32 {
+        lerp(self.sizes.0, self.sizes.1, particle.progress())
+    }
+
+    /// Ages and moves the particles and spawns new ones at `(x, y)`.
+    pub fn update(&mut self, x: f32, y: f32, delta: f32) {
+        let gravity = self.gravity;
+        for particle in &mut self.particles {
+            particle.age += delta;
+            particle.vx += gravity.x * delta;
+            particle.vy += gravity.y * delta;
+            particle.x += particle.vx * delta;
+            particle.y += particle.vy * delta;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+
+        let mut count = std::mem::take(&mut self.burst);
+        if self.emitting && self.rate > 0.0 {
+            self.spawn_timer += delta;
+            let interval = 1.0 / self.rate;
+            while self.spawn_timer >= interval {
+                self.spawn_timer -= interval;
+                count += 1;
+            }
+        }
+
+        let mut rng = rand::thread_rng();
+        let count = count.min(self.max_particles.saturating_sub(self.particles.len()));
+        for _ in 0..count {
+            let speed = random(&mut rng, &self.speed);
+            let angle = random(&mut rng, &self.angle);
+            self.particles.push(Particle {
+                x: x + self.offset.x,
+                y: y + self.offset.y,
+                vx: speed * angle.cos(),
+                vy: speed * angle.sin(),
+                age: 0.0,
+                lifetime: random(&mut rng, &self.lifetime),
+            });
+        }
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Picks a value in the range, which may be empty to always get its start.
+fn random<R: Rng>(rng: &mut R, range: &Range<f32>) -> f32 {
+    if range.start < range.end {
+        rng.gen_range(range.start, range.end)
+    } else {
+        range.start
+    }
+}
+
+/// Simulates every `ParticleEmitter`; add it to the fixed update.
+pub struct ParticleSystem;
+impl<'s> System<'s> for ParticleSystem {
+    type SystemData = (
+        WriteStorage<'s, ParticleEmitter>,
+        ReadStorage<'s, Position>,
+        Read<'s, DeltaTime>,
+    );
+
+    fn run(&mut self, (mut emitters, positions, dt): Self::SystemData) {
+        for (emitter, pos) in (&mut emitters, &positions).join() {
+            emitter.update(pos.x, pos.y, dt.delta);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use ggez::graphics::{self, Drawable};
+use ggez::{mint, Context, GameResult};
+use std::sync::Arc;
+
+use super::batch::PendingBatch;
+use super::components::Shape;
+use super::mesh_cache::MeshCache;
+
+/// The drawing operations the `RenderSystem` needs, so a frame can be drawn
+/// with ggez or recorded without a window.
+pub trait RenderBackend {
+    /// Returns the area of the screen in world coordinates.
+    fn screen_coordinates(&self) -> graphics::Rect;
+
+    /// Measures the width of a single line of text.
+    fn text_width(&mut self, text: &graphics::Text) -> f32;
+
+    /// Applies a transform to everything drawn until it is popped.
+    fn push_transform(&mut self, transform: mint::ColumnMatrix4<f32>) -> GameResult;
+
+    fn pop_transform(&mut self) -> GameResult;
+
+    /// Draws an image, or a white pixel if there is none.
+    fn draw_image(
+        &mut self,
+        image: Option<&Arc<graphics::Image>>,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult;
+
+    fn draw_shape(
+        &mut self,
+        shape: &Shape,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult;
+
+    /// Queues a line of text at `dest`, relative to the next
+    /// `draw_queued_text`.
+    fn queue_text(
+        &mut self,
+        text: &graphics::Text,
+        dest: mint::Point2<f32>,
+        color: graphics::Color,
+    );
+
+    /// Draws all queued text with the same parameters.
+    fn draw_queued_text(
+        &mut self,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult;
+
+    /// Gets called once every entity of the frame has been drawn.
+    fn finish(&mut self) -> GameResult {
+        Ok(())
+    }
+}
+
+/// What the `GgezBackend` keeps between frames.
+#[derive(Debug, Default)]
+pub(crate) struct GraphicsCache {
+    meshes: MeshCache,
+    // A white pixel that images without a texture are drawn with.
+    pixel: Option<Arc<graphics::Image>>,
+}
+
+/// Draws through the ggez context. Consecutive draws of the same image are
+/// batched; the batch is flushed before anything else gets drawn.
+pub(crate) struct GgezBackend<'a> {
+    ctx: &'a mut Context,
+    cache: &'a mut GraphicsCache,
+    batch: Option<PendingBatch>,
+}
+
+impl<'a> GgezBackend<'a> {
+    pub fn new(ctx: &'a mut Context, cache: &'a mut GraphicsCache) -> Self {
+        Self {
+            ctx,
+            cache,
+            batch: None,
+        }
+    }
+
+    fn pixel(&mut self) -> GameResult<Arc<graphics::Image>> {
+        if let Some(pixel) = &self.cache.pixel {
+            return Ok(pixel.clone());
+        }
+        let pixel = Arc::new(graphics::Image::solid(self.ctx, 1, graphics::WHITE)?);
+        self.cache.pixel = Some(pixel.clone());
+        Ok(pixel)
+    }
+
+    fn flush(&mut self) -> GameResult {
+        if let Some(batch) = self.batch.take() {
+            batch.flush(self.ctx)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> RenderBackend for GgezBackend<'a> {
+    fn screen_coordinates(&self) -> graphics::Rect {
+        graphics::screen_coordinates(self.ctx)
+    }
+
+    fn text_width(&mut self, text: &graphics::Text) -> f32 {
+        text.width(self.ctx) as f32
+    }
+
+    fn push_transform(&mut self, transform: mint::ColumnMatrix4<f32>) -> GameResult {
+        self.flush()?;
+        graphics::push_transform(self.ctx, Some(transform));
+        graphics::apply_transformations(self.ctx)
+    }
+
+    fn pop_transform(&mut self) -> GameResult {
+        self.flush()?;
+        graphics::pop_transform(self.ctx);
+        graphics::apply_transformations(self.ctx)
+    }
+
+    fn draw_image(
+        &mut self,
+        image: Option<&Arc<graphics::Image>>,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult {
+        let image = match image {
+            Some(image) => image.clone(),
+            None => self.pixel()?,
+        };
+
+        if !self
+            .batch
+            .as_ref()
+            .is_some_and(|b| b.accepts(&image, blend))
+        {
+            self.flush()?;
+            self.batch = Some(PendingBatch::new(image, blend));
+        }
+        if let Some(b) = self.batch.as_mut() {
+            b.add(params);
+        }
+        Ok(())
+    }
+
+    fn draw_shape(
+        &mut self,
+        shape: &Shape,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult {
+        self.flush()?;
+        let mesh = match self.cache.meshes.get(self.ctx, shape)? {
+            Some(mesh) => mesh,
+            None => return Ok(()),
+        };
+        match blend {
+            Some(blend) => {
+                let mut mesh = mesh.clone();
+                mesh.set_blend_mode(Some(blend));
+                graphics::draw(self.ctx, &mesh, params)
+            }
+            None => graphics::draw(self.ctx, mesh, params),
+        }
+    }
+
+    fn queue_text(
+        &mut self,
+        text: &graphics::Text,
+        dest: mint::Point2<f32>,
+        color: graphics::Color,
+    ) {
+        graphics::queue_text(self.ctx, text, dest, Some(color));
+    }
+
+    fn draw_queued_text(
+        &mut self,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult {
+        self.flush()?;
+        // Pixel fonts stay crisp without smoothing.
+        graphics::draw_queued_text(self.ctx, params, blend, graphics::FilterMode::Nearest)
+    }
+
+    fn finish(&mut self) -> GameResult {
+        self.flush()?;
+        self.cache.meshes.evict_unused();
+        Ok(())
+    }
+}
+
+/// A draw recorded by the `RecordingBackend`.
+#[derive(Clone, Debug)]
+pub enum DrawCommand {
+    PushTransform(mint::ColumnMatrix4<f32>),
+    PopTransform,
+    /// Consecutive draws of the same image with the same blend mode, which
+    /// the `GgezBackend` draws as one sprite batch. A `None` image is a
+    /// white pixel.
+    Images {
+        image: Option<Arc<graphics::Image>>,
+        params: Vec<graphics::DrawParam>,
+        blend: Option<graphics::BlendMode>,
+    },
+    Shape {
+        shape: Shape,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    },
+    /// A line of text at `dest` within the text drawn with `params`.
+    Text {
+        text: String,
+        dest: mint::Point2<f32>,
+        color: graphics::Color,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    },
+}
+
+/// Records the draw commands of the `RenderSystem` in order instead of
+/// drawing them, so rendering can be checked without a window or a GPU.
+/// Text is measured as if every character was `glyph_width` times its font
+/// size wide.
+#[derive(Clone, Debug)]
+pub struct RecordingBackend {
+    pub screen: graphics::Rect,
+    pub glyph_width: f32,
+    commands: Vec<DrawCommand>,
+    queued_text: Vec<(String, mint::Point2<f32>, graphics::Color)>,
+}
+
+impl RecordingBackend {
+    pub fn new(screen_width: f32, screen_height: f32) -> Self {
+        Self {
+            screen: graphics::Rect::new(0.0, 0.0, screen_width, screen_height),
+            glyph_width: 0.5,
+            commands: Vec::new(),
+            queued_text: Vec::new(),
+        }
+    }
+
+    pub fn with_glyph_width(mut self, glyph_width: f32) -> Self {
+        self.glyph_width = glyph_width;
+        self
+    }
+
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    /// Returns the recorded commands and starts a new recording.
+    pub fn take_commands(&mut self) -> Vec<DrawCommand> {
+        std::mem::take(&mut self.commands)
+    }
+}
+
+impl RenderBackend for RecordingBackend {
+    fn screen_coordinates(&self) -> graphics::Rect {
+        self.screen
+    }
+
+    fn text_width(&mut self, text: &graphics::Text) -> f32 {
+        text.fragments()
+            .iter()
+            .map(|fragment| {
+                let font_size = fragment.scale.map_or(16.0, |scale| scale.x);
+                fragment.text.chars().count() as f32 * font_size * self.glyph_width
+            })
+            .sum()
+    }
+
+    fn push_transform(&mut self, transform: mint::ColumnMatrix4<f32>) -> GameResult {
+        self.commands.push(DrawCommand::PushTransform(transform));
+        Ok(())
+    }
+
+    fn pop_transform(&mut self) -> GameResult {
+        self.commands.push(DrawCommand::PopTransform);
+        Ok(())
+    }
+
+    fn draw_image(
+        &mut self,
+        image: Option<&Arc<graphics::Image>>,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult {
+        if let Some(DrawCommand::Images {
+            image: batch_image,
+            params: batch_params,
+            blend: batch_blend,
+        }) = self.commands.last_mut()
+        {
+            let same_image = match (batch_image.as_ref(), image) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            };
+            if same_image && *batch_blend == blend {
+                batch_params.push(params);
+                return Ok(());
+            }
+        }
+
+        self.commands.push(DrawCommand::Images {
+            image: image.cloned(),
+            params: vec![params],
+            blend,
+        });
+        Ok(())
+    }
+
+    fn draw_shape(
+        &mut self,
+        shape: &Shape,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult {
+        self.commands.push(DrawCommand::Shape {
+            shape: shape.clone(),
+            params,
+            blend,
+        });
+        Ok(())
+    }
+
+    fn queue_text(
+        &mut self,
+        text: &graphics::Text,
+        dest: mint::Point2<f32>,
+        color: graphics::Color,
+    ) {
+        self.queued_text.push((text.contents(), dest, color));
+    }
+
+    fn draw_queued_text(
+        &mut self,
+        params: graphics::DrawParam,
+        blend: Option<graphics::BlendMode>,
+    ) -> GameResult {
+        for (text, dest, color) in self.queued_text.drain(..) {
+            self.commands.push(DrawCommand::Text {
+                text,
+                dest,
+                color,
+                params,
+                blend,
+            });
+        }
+        Ok(())
+    }
+}
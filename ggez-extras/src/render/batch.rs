@@ -0,0 +1,48 @@
+use ggez::graphics::{self, spritebatch::SpriteBatch, Drawable};
+use ggez::{Context, GameResult};
+use std::sync::Arc;
+
+/// Collects consecutive image draws that share an image and blend mode, so
+/// they can be drawn with a single `SpriteBatch`.
+pub struct PendingBatch {
+    image: Arc<graphics::Image>,
+    blend: Option<graphics::BlendMode>,
+    params: Vec<graphics::DrawParam>,
+}
+
+impl PendingBatch {
+    pub fn new(image: Arc<graphics::Image>, blend: Option<graphics::BlendMode>) -> Self {
+        Self {
+            image,
+            blend,
+            params: Vec::new(),
+        }
+    }
+
+    /// Returns whether a draw of `image` with `blend` can join this batch.
+    pub fn accepts(
+        &self,
+        image: &Arc<graphics::Image>,
+        blend: Option<graphics::BlendMode>,
+    ) -> bool {
+        self.blend == blend && Arc::ptr_eq(&self.image, image)
+    }
+
+    pub fn add(&mut self, params: graphics::DrawParam) {
+        self.params.push(params);
+    }
+
+    /// Draws the collected sprites; a single sprite is drawn directly.
+    pub fn flush(self, ctx: &mut Context) -> GameResult {
+        if self.params.len() == 1 && self.blend.is_none() {
+            return graphics::draw(ctx, &*self.image, self.params[0]);
+        }
+
+        let mut batch = SpriteBatch::new((*self.image).clone());
+        batch.set_blend_mode(self.blend);
+        for params in self.params {
+            batch.add(params);
+        }
+        graphics::draw(ctx, &batch, graphics::DrawParam::default())
+    }
+}
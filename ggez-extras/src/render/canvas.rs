@@ -0,0 +1,115 @@
+use ggez::{conf, graphics, Context, GameResult};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScalingMode {
+    /// Scales by the largest whole number that fits, so every virtual pixel
+    /// covers the same number of window pixels.
+    Integer,
+    /// Scales as large as fits while keeping the aspect ratio.
+    Letterbox,
+}
+
+/// An offscreen canvas at the virtual resolution of the game, which is
+/// scaled into the window with black bars around it.
+pub struct ScaledCanvas {
+    canvas: graphics::Canvas,
+    width: f32,
+    height: f32,
+    window_width: f32,
+    window_height: f32,
+    mode: ScalingMode,
+}
+
+impl std::fmt::Debug for ScaledCanvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScaledCanvas")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("window_width", &self.window_width)
+            .field("window_height", &self.window_height)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl ScaledCanvas {
+    pub fn new(ctx: &mut Context, width: u16, height: u16, mode: ScalingMode) -> GameResult<Self> {
+        let mut canvas = graphics::Canvas::new(ctx, width, height, conf::NumSamples::One)?;
+        canvas.set_filter(graphics::FilterMode::Nearest);
+        // Resize events report the logical window size, and so does the
+        // drawable size, which ggez takes from the inner size of the window.
+        let (window_width, window_height) = graphics::drawable_size(ctx);
+
+        Ok(Self {
+            canvas,
+            width: width as f32,
+            height: height as f32,
+            window_width,
+            window_height,
+            mode,
+        })
+    }
+
+    /// This method should get called by your resize_event handler, with
+    /// the logical size it gets.
+    pub fn resize(&mut self, window_width: f32, window_height: f32) {
+        self.window_width = window_width;
+        self.window_height = window_height;
+    }
+
+    pub fn set_mode(&mut self, mode: ScalingMode) {
+        self.mode = mode;
+    }
+
+    /// Returns the scale and the window position of the top left corner
+    /// of the scaled canvas.
+    pub fn placement(&self) -> (f32, f32, f32) {
+        let mut scale = (self.window_width / self.width).min(self.window_height / self.height);
+        if self.mode == ScalingMode::Integer && scale >= 1.0 {
+            scale = scale.floor();
+        }
+
+        let x = ((self.window_width - self.width * scale) / 2.0).floor();
+        let y = ((self.window_height - self.height * scale) / 2.0).floor();
+        (scale, x, y)
+    }
+
+    /// Converts a position in the window, like the mouse position, to
+    /// virtual coordinates.
+    pub fn to_virtual(&self, x: f32, y: f32) -> (f32, f32) {
+        let (scale, offset_x, offset_y) = self.placement();
+        ((x - offset_x) / scale, (y - offset_y) / scale)
+    }
+
+    /// Redirects all drawing to the canvas in virtual coordinates.
+    pub fn begin(&self, ctx: &mut Context) -> GameResult {
+        graphics::set_canvas(ctx, Some(&self.canvas));
+        graphics::set_screen_coordinates(ctx, self.virtual_rect())
+    }
+
+    /// Draws the canvas scaled into the window and switches back to
+    /// virtual coordinates.
+    pub fn finish(&self, ctx: &mut Context) -> GameResult {
+        graphics::set_canvas(ctx, None);
+        graphics::set_screen_coordinates(
+            ctx,
+            graphics::Rect::new(0.0, 0.0, self.window_width, self.window_height),
+        )?;
+        graphics::clear(ctx, graphics::BLACK);
+
+        let (scale, x, y) = self.placement();
+        graphics::draw(
+            ctx,
+            &self.canvas,
+            graphics::DrawParam::new()
+                .dest([x, y])
+                .scale([scale, scale]),
+        )?;
+
+        graphics::set_screen_coordinates(ctx, self.virtual_rect())
+    }
+
+    fn virtual_rect(&self) -> graphics::Rect {
+        graphics::Rect::new(0.0, 0.0, self.width, self.height)
+    }
+}
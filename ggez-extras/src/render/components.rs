@@ -0,0 +1,294 @@
+use ggez::{graphics, mint};
+use specs::{Component, FlaggedStorage, VecStorage};
+use std::ops::Range;
+use std::sync::Arc;
+
+use super::sprite_sheet::SpriteSheet;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[allow(dead_code)]
+pub enum Alignment {
+    Left,
+    Right,
+    Centered,
+}
+
+/// Where text is placed within the height of its `Size`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VerticalAlignment {
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Clone, Debug, Component, Default)]
+#[storage(VecStorage)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone, Debug, Component, Default)]
+#[storage(VecStorage)]
+pub struct Size {
+    pub w: f32,
+    pub h: f32,
+}
+
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct Rotation {
+    pub deg: f32,
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Render {
+    pub visible: bool,
+    /// The name of the `RenderLayers` layer the entity is drawn in.
+    pub layer: &'static str,
+}
+
+impl Component for Render {
+    type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
+/// Changes how an entity is drawn: a colour tint and alpha multiplied with
+/// its colour, a scale, mirroring and a blend mode. Images and sprites are
+/// mirrored in place, shapes are mirrored around their position.
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct Style {
+    pub tint: graphics::Color,
+    pub alpha: f32,
+    pub scale: mint::Vector2<f32>,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub blend: Option<graphics::BlendMode>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            tint: graphics::WHITE,
+            alpha: 1.0,
+            scale: mint::Vector2 { x: 1.0, y: 1.0 },
+            flip_x: false,
+            flip_y: false,
+            blend: None,
+        }
+    }
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tint(mut self, tint: graphics::Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    pub fn with_scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = mint::Vector2 { x, y };
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_blend(mut self, blend: graphics::BlendMode) -> Self {
+        self.blend = Some(blend);
+        self
+    }
+
+    /// Returns `color` multiplied with the tint and alpha.
+    pub fn apply(&self, color: graphics::Color) -> graphics::Color {
+        graphics::Color::new(
+            color.r * self.tint.r,
+            color.g * self.tint.g,
+            color.b * self.tint.b,
+            color.a * self.tint.a * self.alpha,
+        )
+    }
+
+    /// Returns the scale with flipped axes negated.
+    pub fn signed_scale(&self) -> mint::Vector2<f32> {
+        mint::Vector2 {
+            x: if self.flip_x {
+                -self.scale.x
+            } else {
+                self.scale.x
+            },
+            y: if self.flip_y {
+                -self.scale.y
+            } else {
+                self.scale.y
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct Image {
+    pub image: Arc<graphics::Image>,
+}
+
+/// Draws a single frame of a `SpriteSheet`.
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct Sprite {
+    pub sheet: Arc<SpriteSheet>,
+    pub frame: usize,
+}
+
+impl Sprite {
+    pub fn new(sheet: Arc<SpriteSheet>, frame: usize) -> Self {
+        Self { sheet, frame }
+    }
+
+    /// Creates a sprite showing the frame with the given name, if the
+    /// sheet has one.
+    pub fn named(sheet: Arc<SpriteSheet>, name: &str) -> Option<Self> {
+        sheet.find(name).map(|frame| Self { sheet, frame })
+    }
+}
+
+/// Draws a copy of the text behind it, so it stays readable on busy
+/// backgrounds.
+#[derive(Clone, Debug)]
+pub enum TextEffect {
+    Shadow {
+        offset: mint::Vector2<f32>,
+        color: graphics::Color,
+    },
+    Outline {
+        width: f32,
+        color: graphics::Color,
+    },
+}
+
+/// Colours the bytes of `Text::text` within `range`, which must start and
+/// end on character boundaries.
+#[derive(Clone, Debug)]
+pub struct TextSpan {
+    pub range: Range<usize>,
+    pub color: graphics::Color,
+}
+
+/// Text laid out within the box of the entity `Size`. Without a `Size`, right
+/// aligned and centered text use the width of the screen. Lines are broken
+/// at newlines and, when `wrap` is set, at spaces to fit `Size.w`.
+#[derive(Clone, Debug)]
+pub struct Text {
+    pub text: String,
+    pub font_size: f32,
+    pub font: graphics::Font,
+    pub align: Alignment,
+    pub valign: VerticalAlignment,
+    pub color: graphics::Color,
+    pub wrap: bool,
+    /// The distance between lines as a multiple of the font size.
+    pub line_spacing: f32,
+    pub effect: Option<TextEffect>,
+    /// Colours that override `color`; later spans win where they overlap.
+    pub spans: Vec<TextSpan>,
+}
+
+impl Component for Text {
+    type Storage = FlaggedStorage<Self, VecStorage<Self>>;
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            font_size: 16.0,
+            font: graphics::Font::default(),
+            align: Alignment::Left,
+            valign: VerticalAlignment::Top,
+            color: graphics::WHITE,
+            wrap: false,
+            line_spacing: 1.0,
+            effect: None,
+            spans: Vec::new(),
+        }
+    }
+}
+
+/// The geometry of a `Shape`, relative to the entity `Position`. Rectangles
+/// start at the position, circles are centered on it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShapeKind {
+    Rectangle { w: f32, h: f32 },
+    Circle { radius: f32 },
+    Line { points: Vec<mint::Point2<f32>> },
+    Polygon { points: Vec<mint::Point2<f32>> },
+}
+
+/// Whether a `Shape` is filled or outlined with the given stroke width.
+/// Lines are always drawn with the stroke width, or 1.0 when filled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ShapeMode {
+    Fill,
+    Stroke(f32),
+}
+
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct Shape {
+    pub kind: ShapeKind,
+    pub mode: ShapeMode,
+    pub color: graphics::Color,
+}
+
+impl Shape {
+    pub fn rectangle(w: f32, h: f32, color: graphics::Color) -> Self {
+        Self {
+            kind: ShapeKind::Rectangle { w, h },
+            mode: ShapeMode::Fill,
+            color,
+        }
+    }
+
+    pub fn circle(radius: f32, color: graphics::Color) -> Self {
+        Self {
+            kind: ShapeKind::Circle { radius },
+            mode: ShapeMode::Fill,
+            color,
+        }
+    }
+
+    pub fn line(points: Vec<mint::Point2<f32>>, width: f32, color: graphics::Color) -> Self {
+        Self {
+            kind: ShapeKind::Line { points },
+            mode: ShapeMode::Stroke(width),
+            color,
+        }
+    }
+
+    pub fn polygon(points: Vec<mint::Point2<f32>>, color: graphics::Color) -> Self {
+        Self {
+            kind: ShapeKind::Polygon { points },
+            mode: ShapeMode::Fill,
+            color,
+        }
+    }
+
+    /// Outlines the shape instead of filling it.
+    pub fn stroke(mut self, width: f32) -> Self {
+        self.mode = ShapeMode::Stroke(width);
+        self
+    }
+}
@@ -0,0 +1,258 @@
+use ggez::{graphics, mint, GameResult};
+use specs::{Component, Entity, Join, World, WorldExt};
+
+use super::backend::RenderBackend;
+use super::components::{Position, Render, Shape, Size};
+use super::layers::{LayerSpace, RenderLayers};
+use crate::camera::Camera;
+
+const BOX_COLOR: graphics::Color = graphics::Color {
+    r: 0.0,
+    g: 1.0,
+    b: 0.0,
+    a: 0.8,
+};
+const VECTOR_COLOR: graphics::Color = graphics::Color {
+    r: 0.2,
+    g: 0.6,
+    b: 1.0,
+    a: 1.0,
+};
+const CONTACT_COLOR: graphics::Color = graphics::Color {
+    r: 1.0,
+    g: 0.0,
+    b: 0.0,
+    a: 1.0,
+};
+
+type VectorFn = Box<dyn Fn(&World) -> Vec<(Entity, mint::Vector2<f32>)> + Send + Sync>;
+
+/// A resource that makes the `RenderSystem` draw the box of every entity,
+/// its id, its velocity and the collision contacts of the last tick on top
+/// of the scene. Nothing is drawn or recorded unless it is enabled.
+#[derive(Default)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+    pub font: graphics::Font,
+    vectors: Option<VectorFn>,
+    contacts: Vec<graphics::Rect>,
+    last_contacts: Vec<graphics::Rect>,
+}
+
+impl std::fmt::Debug for DebugOverlay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DebugOverlay")
+            .field("enabled", &self.enabled)
+            .field("contacts", &self.last_contacts)
+            .finish()
+    }
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_font(mut self, font: graphics::Font) -> Self {
+        self.font = font;
+        self
+    }
+
+    /// Draws a vector, like the velocity, from the center of every entity
+    /// with a `C` component. The vector is drawn in world units, so scale it
+    /// to a readable length.
+    pub fn with_vectors<C, F>(mut self, vector: F) -> Self
+    where
+        C: Component,
+        F: Fn(&C) -> mint::Vector2<f32> + Send + Sync + 'static,
+    {
+        self.vectors = Some(Box::new(move |world: &World| {
+            (&world.entities(), &world.read_storage::<C>())
+                .join()
+                .map(|(entity, component)| (entity, vector(component)))
+                .collect()
+        }));
+        self
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// Records a collision of the current tick, in world coordinates.
+    pub fn add_contact(&mut self, contact: graphics::Rect) {
+        if self.enabled {
+            self.contacts.push(contact);
+        }
+    }
+
+    /// Shows the contacts of the tick that just ended; call it after every
+    /// fixed update.
+    pub fn end_tick(&mut self) {
+        self.last_contacts = std::mem::take(&mut self.contacts);
+    }
+
+    /// Draws the overlay. The parts of entities in world layers are drawn
+    /// through the camera with the parallax of their layer, like the
+    /// entities themselves; screen space layers are drawn without it.
+    pub fn draw(&self, backend: &mut dyn RenderBackend, world: &World) -> GameResult {
+        let layers = world.try_fetch::<RenderLayers>();
+        let positions = world.read_storage::<Position>();
+        let sizes = world.read_storage::<Size>();
+        let renders = world.read_storage::<Render>();
+
+        // The parallax an entity is drawn with, or `None` in screen space.
+        // Entities without a known layer are drawn like a plain world layer.
+        let parallax = |entity: Entity| -> Option<f32> {
+            match renders
+                .get(entity)
+                .and_then(|render| layers.as_ref().and_then(|layers| layers.get(render.layer)))
+            {
+                Some(layer) if layer.space == LayerSpace::Screen => None,
+                Some(layer) => Some(layer.parallax),
+                None => Some(1.0),
+            }
+        };
+
+        let mut world_shapes = LayerShapes::new();
+        let mut screen_shapes = DebugShapes::new();
+
+        for (entity, pos, size) in (&world.entities(), &positions, (&sizes).maybe()).join() {
+            let shapes = match parallax(entity) {
+                Some(parallax) => world_shapes.get(parallax),
+                None => &mut screen_shapes,
+            };
+
+            match size {
+                Some(size) => {
+                    shapes.rectangle(graphics::Rect::new(pos.x, pos.y, size.w, size.h), BOX_COLOR)
+                }
+                None => {
+                    shapes.line(pos.x - 2.0, pos.y, pos.x + 2.0, pos.y, BOX_COLOR);
+                    shapes.line(pos.x, pos.y - 2.0, pos.x, pos.y + 2.0, BOX_COLOR);
+                }
+            }
+            shapes.label(entity.id().to_string(), pos.x + 1.0, pos.y + 1.0);
+        }
+
+        if let Some(vectors) = &self.vectors {
+            for (entity, vector) in vectors(world) {
+                let (x, y) = match (positions.get(entity), sizes.get(entity)) {
+                    (Some(pos), Some(size)) => (pos.x + size.w / 2.0, pos.y + size.h / 2.0),
+                    (Some(pos), None) => (pos.x, pos.y),
+                    (None, _) => continue,
+                };
+                let shapes = match parallax(entity) {
+                    Some(parallax) => world_shapes.get(parallax),
+                    None => &mut screen_shapes,
+                };
+                shapes.line(x, y, x + vector.x, y + vector.y, VECTOR_COLOR);
+            }
+        }
+
+        // Contacts are in plain world coordinates.
+        for contact in &self.last_contacts {
+            world_shapes.get(1.0).rectangle(*contact, CONTACT_COLOR);
+        }
+
+        let camera = world.try_fetch::<Camera>();
+        for (parallax, shapes) in world_shapes.layers {
+            if let Some(camera) = &camera {
+                backend.push_transform(camera.parallax_transform(parallax))?;
+            }
+            shapes.draw(backend, self.font)?;
+            if camera.is_some() {
+                backend.pop_transform()?;
+            }
+        }
+
+        screen_shapes.draw(backend, self.font)
+    }
+}
+
+/// The shapes of the overlay per parallax factor of the world layers.
+struct LayerShapes {
+    layers: Vec<(f32, DebugShapes)>,
+}
+
+impl LayerShapes {
+    fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    fn get(&mut self, parallax: f32) -> &mut DebugShapes {
+        let index = match self
+            .layers
+            .iter()
+            .position(|(p, _)| p.to_bits() == parallax.to_bits())
+        {
+            Some(index) => index,
+            None => {
+                self.layers.push((parallax, DebugShapes::new()));
+                self.layers.len() - 1
+            }
+        };
+        &mut self.layers[index].1
+    }
+}
+
+/// Collects the outlines and labels of the overlay, which are drawn as
+/// shapes and text by the backend.
+struct DebugShapes {
+    shapes: Vec<(Shape, mint::Point2<f32>)>,
+    labels: Vec<(String, mint::Point2<f32>)>,
+}
+
+impl DebugShapes {
+    fn new() -> Self {
+        Self {
+            shapes: Vec::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    fn rectangle(&mut self, rect: graphics::Rect, color: graphics::Color) {
+        let shape = Shape::rectangle(rect.w, rect.h, color).stroke(1.0);
+        self.shapes.push((shape, rect.point()));
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: graphics::Color) {
+        // Lines without length cannot be tessellated.
+        if (x2 - x1).abs() < 0.5 && (y2 - y1).abs() < 0.5 {
+            return;
+        }
+        // Lines start at the origin, so lines of the same length and
+        // direction share a mesh.
+        let points = vec![
+            mint::Point2 { x: 0.0, y: 0.0 },
+            mint::Point2 {
+                x: x2 - x1,
+                y: y2 - y1,
+            },
+        ];
+        self.shapes.push((
+            Shape::line(points, 1.0, color),
+            mint::Point2 { x: x1, y: y1 },
+        ));
+    }
+
+    fn label(&mut self, text: String, x: f32, y: f32) {
+        self.labels.push((text, mint::Point2 { x, y }));
+    }
+
+    fn draw(self, backend: &mut dyn RenderBackend, font: graphics::Font) -> GameResult {
+        for (shape, dest) in &self.shapes {
+            let params = graphics::DrawParam::new().dest(*dest).color(shape.color);
+            backend.draw_shape(shape, params, None)?;
+        }
+
+        if self.labels.is_empty() {
+            return Ok(());
+        }
+        for (text, dest) in self.labels {
+            let text = graphics::Text::new((text, font, 8.0));
+            backend.queue_text(&text, dest, BOX_COLOR);
+        }
+        backend.draw_queued_text(graphics::DrawParam::default(), None)
+    }
+}
@@ -0,0 +1,82 @@
+/// Whether a layer is drawn through the `Camera` or fixed to the screen.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LayerSpace {
+    World,
+    Screen,
+}
+
+/// The settings of a named layer of the `RenderLayers` resource.
+#[derive(Clone, Debug)]
+pub struct RenderLayer {
+    pub name: &'static str,
+    pub space: LayerSpace,
+    /// How much of the camera movement a world layer follows; 1.0 moves with
+    /// the camera, lower values move slower so the layer looks further away.
+    pub parallax: f32,
+    pub visible: bool,
+}
+
+impl RenderLayer {
+    fn new(name: &'static str, space: LayerSpace, parallax: f32) -> Self {
+        Self {
+            name,
+            space,
+            parallax,
+            visible: true,
+        }
+    }
+}
+
+/// The layers entities are drawn in, from back to front. A `Render` component
+/// names its layer; entities within a layer are drawn in the order they were
+/// added. Entities naming an unknown layer are drawn on top of all layers.
+#[derive(Clone, Debug, Default)]
+pub struct RenderLayers {
+    layers: Vec<RenderLayer>,
+}
+
+impl RenderLayers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer drawn through the camera on top of the previous layers.
+    pub fn world(mut self, name: &'static str, parallax: f32) -> Self {
+        self.layers
+            .push(RenderLayer::new(name, LayerSpace::World, parallax));
+        self
+    }
+
+    /// Adds a layer in screen coordinates on top of the previous layers.
+    pub fn screen(mut self, name: &'static str) -> Self {
+        self.layers
+            .push(RenderLayer::new(name, LayerSpace::Screen, 1.0));
+        self
+    }
+
+    /// Returns the position of the layer in the draw order.
+    pub fn order(&self, name: &str) -> usize {
+        self.layers
+            .iter()
+            .position(|layer| layer.name == name)
+            .unwrap_or(self.layers.len())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RenderLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut RenderLayer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    pub fn set_visible(&mut self, name: &str, visible: bool) {
+        if let Some(layer) = self.get_mut(name) {
+            layer.visible = visible;
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RenderLayer> {
+        self.layers.iter()
+    }
+}
@@ -0,0 +1,267 @@
+use ggez::{graphics, Context, GameResult};
+use log::warn;
+use std::collections::{hash_map::Entry, HashMap};
+use std::hash::{Hash, Hasher};
+
+use super::components::{Shape, ShapeKind, ShapeMode};
+
+/// The geometry of a shape, which is all a cached mesh depends on. The colour
+/// is applied when drawing, so differently coloured shapes share a mesh.
+#[derive(Clone, Debug)]
+struct MeshKey {
+    kind: ShapeKind,
+    mode: ShapeMode,
+    /// The kind, the floats and the mode, which the key is compared and
+    /// hashed by.
+    bits: Vec<u32>,
+}
+
+impl MeshKey {
+    /// Returns the key of `shape`, or `None` if ggez cannot build a mesh for
+    /// it: for floats that are not finite, sizes that are not positive, and
+    /// lines or polygons with too few points.
+    fn new(shape: &Shape) -> Option<Self> {
+        let mut floats = Vec::new();
+        let (tag, valid) = match &shape.kind {
+            ShapeKind::Rectangle { w, h } => {
+                floats.extend_from_slice(&[*w, *h]);
+                (0, *w > 0.0 && *h > 0.0)
+            }
+            ShapeKind::Circle { radius } => {
+                floats.push(*radius);
+                (1, *radius > 0.0)
+            }
+            ShapeKind::Line { points } => {
+                floats.extend(points.iter().flat_map(|p| [p.x, p.y].to_vec()));
+                // A line that goes nowhere has no triangles either.
+                let has_length = points.iter().any(|p| *p != points[0]);
+                (2, points.len() >= 2 && has_length)
+            }
+            ShapeKind::Polygon { points } => {
+                floats.extend(points.iter().flat_map(|p| [p.x, p.y].to_vec()));
+                (3, points.len() >= 3)
+            }
+        };
+        let width = match shape.mode {
+            ShapeMode::Fill => None,
+            ShapeMode::Stroke(width) => Some(width),
+        };
+        floats.extend(width);
+        if !valid || width.is_some_and(|width| width <= 0.0) {
+            return None;
+        }
+        if floats.iter().any(|f| !f.is_finite()) {
+            return None;
+        }
+
+        // -0.0 and 0.0 draw the same, so they share a mesh.
+        let mut bits = vec![tag, floats.len() as u32, width.is_some() as u32];
+        bits.extend(floats.iter().map(|f| (f + 0.0).to_bits()));
+        Some(Self {
+            kind: shape.kind.clone(),
+            mode: shape.mode,
+            bits,
+        })
+    }
+}
+
+impl PartialEq for MeshKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+impl Eq for MeshKey {}
+
+impl Hash for MeshKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits.hash(state);
+    }
+}
+
+struct CachedMesh {
+    mesh: graphics::Mesh,
+    used: bool,
+}
+
+/// Builds the meshes for `Shape` components once and reuses them for every
+/// frame and every entity with the same geometry.
+#[derive(Default)]
+pub struct MeshCache {
+    meshes: HashMap<MeshKey, CachedMesh>,
+}
+
+impl std::fmt::Debug for MeshCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MeshCache")
+            .field("meshes", &self.meshes.len())
+            .finish()
+    }
+}
+
+impl MeshCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the mesh for the given shape, building it if needed, or
+    /// `None` for a shape that has no mesh, which is skipped with a warning
+    /// rather than failing the frame.
+    pub fn get(&mut self, ctx: &mut Context, shape: &Shape) -> GameResult<Option<&graphics::Mesh>> {
+        let key = match MeshKey::new(shape) {
+            Some(key) => key,
+            None => {
+                warn!("Skipping a shape that cannot be drawn: {:?}", shape.kind);
+                return Ok(None);
+            }
+        };
+
+        let cached = match self.meshes.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let mesh = Self::build(ctx, entry.key())?;
+                entry.insert(CachedMesh { mesh, used: false })
+            }
+        };
+        cached.used = true;
+        Ok(Some(&cached.mesh))
+    }
+
+    /// Drops the meshes that were not used since the last call, so shapes
+    /// that change every frame don't grow the cache forever.
+    pub fn evict_unused(&mut self) {
+        self.meshes.retain(|_, cached| cached.used);
+        for cached in self.meshes.values_mut() {
+            cached.used = false;
+        }
+    }
+
+    fn build(ctx: &mut Context, key: &MeshKey) -> GameResult<graphics::Mesh> {
+        let mode = match key.mode {
+            ShapeMode::Fill => graphics::DrawMode::fill(),
+            ShapeMode::Stroke(width) => graphics::DrawMode::stroke(width),
+        };
+
+        match &key.kind {
+            ShapeKind::Rectangle { w, h } => graphics::Mesh::new_rectangle(
+                ctx,
+                mode,
+                graphics::Rect::new(0.0, 0.0, *w, *h),
+                graphics::WHITE,
+            ),
+            ShapeKind::Circle { radius } => {
+                graphics::Mesh::new_circle(ctx, mode, [0.0, 0.0], *radius, 0.1, graphics::WHITE)
+            }
+            ShapeKind::Line { points } => {
+                let width = match key.mode {
+                    ShapeMode::Fill => 1.0,
+                    ShapeMode::Stroke(width) => width,
+                };
+                graphics::Mesh::new_line(ctx, points, width, graphics::WHITE)
+            }
+            ShapeKind::Polygon { points } => {
+                graphics::Mesh::new_polygon(ctx, mode, points, graphics::WHITE)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ggez::mint::Point2;
+
+    fn key(kind: ShapeKind, mode: ShapeMode) -> Option<MeshKey> {
+        MeshKey::new(&Shape {
+            kind,
+            mode,
+            color: graphics::WHITE,
+        })
+    }
+
+    fn points(points: &[(f32, f32)]) -> Vec<Point2<f32>> {
+        points.iter().map(|&(x, y)| Point2 { x, y }).collect()
+    }
+
+    #[test]
+    fn shares_the_key_of_zero_and_negative_zero() {
+        let zero = key(
+            ShapeKind::Line {
+                points: points(&[(0.0, 0.0), (4.0, 2.0)]),
+            },
+            ShapeMode::Fill,
+        );
+        let negative = key(
+            ShapeKind::Line {
+                points: points(&[(-0.0, -0.0), (4.0, 2.0)]),
+            },
+            ShapeMode::Fill,
+        );
+
+        assert_eq!(zero, negative);
+    }
+
+    #[test]
+    fn tells_kinds_and_modes_apart() {
+        let square = points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        let line = key(
+            ShapeKind::Line {
+                points: square.clone(),
+            },
+            ShapeMode::Fill,
+        );
+        let polygon = key(ShapeKind::Polygon { points: square }, ShapeMode::Fill);
+        let filled = key(ShapeKind::Rectangle { w: 2.0, h: 1.0 }, ShapeMode::Fill);
+        let stroked = key(
+            ShapeKind::Rectangle { w: 2.0, h: 1.0 },
+            ShapeMode::Stroke(1.0),
+        );
+
+        assert_ne!(line, polygon);
+        assert_ne!(filled, stroked);
+    }
+
+    #[test]
+    fn has_no_key_for_shapes_without_a_mesh() {
+        let invalid = [
+            key(ShapeKind::Rectangle { w: 0.0, h: 1.0 }, ShapeMode::Fill),
+            key(
+                ShapeKind::Rectangle {
+                    w: f32::NAN,
+                    h: 1.0,
+                },
+                ShapeMode::Fill,
+            ),
+            key(ShapeKind::Circle { radius: 0.0 }, ShapeMode::Fill),
+            key(
+                ShapeKind::Circle {
+                    radius: f32::INFINITY,
+                },
+                ShapeMode::Fill,
+            ),
+            key(ShapeKind::Circle { radius: 1.0 }, ShapeMode::Stroke(0.0)),
+            key(
+                ShapeKind::Line {
+                    points: points(&[(1.0, 1.0)]),
+                },
+                ShapeMode::Fill,
+            ),
+            key(
+                ShapeKind::Line {
+                    points: points(&[(1.0, 1.0), (1.0, 1.0)]),
+                },
+                ShapeMode::Fill,
+            ),
+            key(
+                ShapeKind::Polygon {
+                    points: points(&[(0.0, 0.0), (1.0, 1.0)]),
+                },
+                ShapeMode::Fill,
+            ),
+        ];
+
+        for key in invalid.iter() {
+            assert_eq!(*key, None);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! A reusable, layered renderer for entities with a `Render` component.
+use specs::{World, WorldExt};
+
+use crate::particles::ParticleEmitter;
+
+pub mod backend;
+mod batch;
+pub mod canvas;
+pub mod components;
+pub mod debug;
+pub mod layers;
+pub mod mesh_cache;
+pub mod sprite_sheet;
+pub mod system;
+mod text_layout;
+
+pub use backend::*;
+pub use canvas::*;
+pub use components::*;
+pub use debug::*;
+pub use layers::*;
+pub use mesh_cache::*;
+pub use sprite_sheet::*;
+pub use system::*;
+
+/// Registers all the components used by the `RenderSystem`.
+pub fn register(world: &mut World) {
+    world.register::<Position>();
+    world.register::<Size>();
+    world.register::<Rotation>();
+    world.register::<Render>();
+    world.register::<Style>();
+    world.register::<Image>();
+    world.register::<Sprite>();
+    world.register::<Text>();
+    world.register::<Shape>();
+    world.register::<ParticleEmitter>();
+}
@@ -0,0 +1,179 @@
+use ggez::{filesystem, graphics, Context, GameError, GameResult};
+use serde_derive::Deserialize;
+use std::collections::HashMap;
+use std::path;
+use std::sync::Arc;
+
+/// A frame of a TexturePacker "JSON (Array)" description.
+#[derive(Debug, Deserialize)]
+struct JsonFrame {
+    filename: String,
+    frame: JsonRect,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSheet {
+    frames: Vec<JsonFrame>,
+}
+
+/// An image split into frames, which `Sprite` components draw one at a time.
+/// Frames are stored in pixels and can be looked up by index or by name.
+#[derive(Clone, Debug)]
+pub struct SpriteSheet {
+    image: Arc<graphics::Image>,
+    frames: Vec<graphics::Rect>,
+    names: HashMap<String, usize>,
+}
+
+impl SpriteSheet {
+    /// Slices the image into a grid of equally sized frames, row by row.
+    /// Fails for frames without a size or larger than the image.
+    pub fn from_grid(image: Arc<graphics::Image>, frame_w: u16, frame_h: u16) -> GameResult<Self> {
+        let frames = grid(image.width(), image.height(), frame_w, frame_h)?;
+        Ok(Self {
+            image,
+            frames,
+            names: HashMap::new(),
+        })
+    }
+
+    /// Loads the frames of the image from a TexturePacker "JSON (Array)" file,
+    /// naming every frame after its `filename`.
+    pub fn from_json<P: AsRef<path::Path>>(
+        ctx: &mut Context,
+        image: Arc<graphics::Image>,
+        path: P,
+    ) -> GameResult<Self> {
+        let file = filesystem::open(ctx, path.as_ref())?;
+        let sheet: JsonSheet = serde_json::from_reader(file).map_err(|e| {
+            GameError::ResourceLoadError(format!(
+                "Unable to parse sprite sheet {:?}: {}",
+                path.as_ref(),
+                e
+            ))
+        })?;
+
+        let mut frames = Vec::new();
+        let mut names = HashMap::new();
+        for (index, frame) in sheet.frames.into_iter().enumerate() {
+            frames.push(graphics::Rect::new(
+                frame.frame.x,
+                frame.frame.y,
+                frame.frame.w,
+                frame.frame.h,
+            ));
+            names.insert(frame.filename, index);
+        }
+
+        Ok(Self {
+            image,
+            frames,
+            names,
+        })
+    }
+
+    /// Names the frame at `index`, so it can be found with `find`.
+    pub fn with_name(mut self, name: &str, index: usize) -> Self {
+        self.names.insert(name.to_string(), index);
+        self
+    }
+
+    /// Replaces the image and keeps the frames, e.g. for a reloaded image
+    /// with the same layout.
+    pub fn with_image(mut self, image: Arc<graphics::Image>) -> Self {
+        self.image = image;
+        self
+    }
+
+    pub fn image(&self) -> &Arc<graphics::Image> {
+        &self.image
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the index of the frame with the given name.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.names.get(name).cloned()
+    }
+
+    /// Returns the frame at `index` in pixels.
+    pub fn frame(&self, index: usize) -> Option<graphics::Rect> {
+        self.frames.get(index).cloned()
+    }
+
+    /// Returns the frame at `index` in the normalized coordinates expected
+    /// by `DrawParam::src`.
+    pub fn src(&self, index: usize) -> Option<graphics::Rect> {
+        let w = self.image.width() as f32;
+        let h = self.image.height() as f32;
+        self.frame(index)
+            .map(|f| graphics::Rect::new(f.x / w, f.y / h, f.w / w, f.h / h))
+    }
+}
+
+/// Returns the frames of a `width` by `height` image split into a grid of
+/// `frame_w` by `frame_h` frames, row by row.
+fn grid(width: u16, height: u16, frame_w: u16, frame_h: u16) -> GameResult<Vec<graphics::Rect>> {
+    if frame_w == 0 || frame_h == 0 || frame_w > width || frame_h > height {
+        return Err(GameError::ResourceLoadError(format!(
+            "Unable to split a {}x{} image into {}x{} frames",
+            width, height, frame_w, frame_h
+        )));
+    }
+
+    let mut frames = Vec::new();
+    for row in 0..height / frame_h {
+        for column in 0..width / frame_w {
+            frames.push(graphics::Rect::new(
+                (column * frame_w) as f32,
+                (row * frame_h) as f32,
+                frame_w as f32,
+                frame_h as f32,
+            ));
+        }
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_grid_row_by_row() {
+        let frames = grid(30, 20, 10, 10).unwrap();
+
+        assert_eq!(frames.len(), 6);
+        assert_eq!(frames[1], graphics::Rect::new(10.0, 0.0, 10.0, 10.0));
+        assert_eq!(frames[3], graphics::Rect::new(0.0, 10.0, 10.0, 10.0));
+    }
+
+    #[test]
+    fn leaves_out_partial_frames() {
+        let frames = grid(25, 15, 10, 10).unwrap();
+
+        assert_eq!(frames.len(), 2);
+    }
+
+    #[test]
+    fn fails_for_empty_or_oversized_frames() {
+        assert!(grid(30, 20, 0, 10).is_err());
+        assert!(grid(30, 20, 10, 0).is_err());
+        assert!(grid(30, 20, 40, 10).is_err());
+        assert!(grid(30, 20, 10, 30).is_err());
+    }
+}
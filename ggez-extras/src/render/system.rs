@@ -0,0 +1,703 @@
+use ggez::graphics;
+use ggez::{mint, timer, Context, GameResult};
+use mint::Point2;
+use specs::{prelude::ComponentEvent, BitSet, Entity, Join, ReaderId, World, WorldExt};
+use specs_guided_join::GuidedJoin;
+
+use super::backend::{GgezBackend, GraphicsCache, RenderBackend};
+use super::canvas::ScaledCanvas;
+use super::components::*;
+use super::debug::DebugOverlay;
+use super::layers::{LayerSpace, RenderLayers};
+use super::text_layout::TextCache;
+use crate::camera::Camera;
+use crate::particles::ParticleEmitter;
+
+#[derive(Debug)]
+pub struct RenderSystem {
+    // These keep track of where you left off in the event channels.
+    reader_id: ReaderId<ComponentEvent>,
+    text_reader_id: ReaderId<ComponentEvent>,
+    sorted_entities: Vec<Entity>,
+    dirty_sort: bool,
+    show_fps: bool,
+    fps_font: graphics::Font,
+    // The FPS of the context, taken when drawing through it.
+    fps: f64,
+    graphics_cache: GraphicsCache,
+    text_cache: TextCache,
+    canvas: Option<ScaledCanvas>,
+    inserted: BitSet,
+    deleted: BitSet,
+}
+
+impl RenderSystem {
+    pub fn new(world: &mut World) -> Self {
+        let mut render_components = world.write_storage::<Render>();
+        let mut text_components = world.write_storage::<Text>();
+        Self {
+            reader_id: render_components.register_reader(),
+            text_reader_id: text_components.register_reader(),
+            sorted_entities: Vec::new(),
+            dirty_sort: true,
+            show_fps: true,
+            fps_font: graphics::Font::default(),
+            fps: 0.0,
+            graphics_cache: GraphicsCache::default(),
+            text_cache: TextCache::new(),
+            canvas: None,
+            inserted: BitSet::new(),
+            deleted: BitSet::new(),
+        }
+    }
+
+    /// Sets the font used to draw the FPS counter.
+    pub fn with_fps_font(mut self, font: graphics::Font) -> Self {
+        self.fps_font = font;
+        self
+    }
+
+    /// Changes the font of the FPS counter, for fonts that are loaded after
+    /// the renderer is created.
+    pub fn set_fps_font(&mut self, font: graphics::Font) {
+        self.fps_font = font;
+    }
+
+    /// Sets whether the FPS counter is drawn, which it is by default.
+    pub fn with_show_fps(mut self, show_fps: bool) -> Self {
+        self.show_fps = show_fps;
+        self
+    }
+
+    pub fn set_show_fps(&mut self, show_fps: bool) {
+        self.show_fps = show_fps;
+    }
+
+    /// Renders every frame to the given canvas before scaling it into
+    /// the window.
+    pub fn with_canvas(mut self, canvas: ScaledCanvas) -> Self {
+        self.canvas = Some(canvas);
+        self
+    }
+
+    /// This method should get called by your resize_event handler.
+    pub fn resize(&mut self, window_width: f32, window_height: f32) {
+        if let Some(canvas) = &mut self.canvas {
+            canvas.resize(window_width, window_height);
+        }
+    }
+
+    fn sort_entities(&mut self, world: &World) {
+        self.inserted.clear();
+        self.deleted.clear();
+
+        let render_entities = world.write_storage::<Render>();
+        for event in render_entities.channel().read(&mut self.reader_id) {
+            match event {
+                ComponentEvent::Modified(_) => {
+                    self.dirty_sort = true;
+                }
+                ComponentEvent::Inserted(id) => {
+                    self.inserted.add(*id);
+                    self.dirty_sort = true;
+                }
+                ComponentEvent::Removed(id) => {
+                    self.deleted.add(*id);
+                }
+            };
+        }
+
+        for e in &self.deleted {
+            self.sorted_entities.retain(|r| r.id() != e);
+        }
+
+        for (e, _) in (&world.entities(), &self.inserted).join() {
+            self.sorted_entities.push(e);
+        }
+        if self.dirty_sort {
+            // The sort is stable, so entities keep the order they were
+            // added in within their layer.
+            let layers = world.try_fetch::<RenderLayers>();
+            self.sorted_entities.sort_by_key(|e| {
+                let layer = render_entities.get(*e).unwrap().layer;
+                layers.as_ref().map_or(0, |layers| layers.order(layer))
+            });
+            self.dirty_sort = false;
+        }
+    }
+
+    /// Drops the cached layouts of text that changed since the last frame.
+    fn invalidate_text(&mut self, world: &World) {
+        let text_components = world.read_storage::<Text>();
+        for event in text_components.channel().read(&mut self.text_reader_id) {
+            match event {
+                ComponentEvent::Modified(id)
+                | ComponentEvent::Inserted(id)
+                | ComponentEvent::Removed(id) => self.text_cache.invalidate(*id),
+            }
+        }
+    }
+
+    /// Clears the screen, draws all entities and presents the frame.
+    pub fn run(&mut self, ctx: &mut Context, world: &World) -> GameResult {
+        if let Some(canvas) = &self.canvas {
+            canvas.begin(ctx)?;
+        }
+
+        graphics::clear(ctx, graphics::Color::from_rgb(40, 45, 52));
+        self.draw(ctx, world)?;
+
+        if let Some(canvas) = &self.canvas {
+            canvas.finish(ctx)?;
+        }
+        graphics::present(ctx)?;
+
+        Ok(())
+    }
+
+    /// Draws all entities without clearing or presenting, so games can
+    /// draw things of their own in the same frame.
+    pub fn draw(&mut self, ctx: &mut Context, world: &World) -> GameResult {
+        self.fps = timer::fps(ctx);
+
+        // The backend borrows the cache while the system draws into it.
+        let mut cache = std::mem::take(&mut self.graphics_cache);
+        let result = self.draw_with(&mut GgezBackend::new(ctx, &mut cache), world);
+        self.graphics_cache = cache;
+        result
+    }
+
+    /// Draws all entities, the debug overlay and the FPS counter with the
+    /// given backend, e.g. a `RecordingBackend` to check what gets drawn
+    /// without a window.
+    pub fn draw_with(&mut self, backend: &mut dyn RenderBackend, world: &World) -> GameResult {
+        self.sort_entities(world);
+        self.invalidate_text(world);
+
+        let layers = world.try_fetch::<RenderLayers>();
+        let camera = world.try_fetch::<Camera>();
+
+        // The layer being drawn, whether it is visible and whether a camera
+        // transform was pushed for it.
+        let mut current_layer: Option<&'static str> = None;
+        let mut layer_visible = true;
+        let mut transformed = false;
+
+        for (entity, render, pos, size, rotation, style, image, sprite, text, shape, emitter) in (
+            &world.entities(),
+            &world.read_storage::<Render>(),
+            &world.read_storage::<Position>(),
+            (&world.read_storage::<Size>()).maybe(),
+            (&world.read_storage::<Rotation>()).maybe(),
+            (&world.read_storage::<Style>()).maybe(),
+            (&world.read_storage::<Image>()).maybe(),
+            (&world.read_storage::<Sprite>()).maybe(),
+            (&world.read_storage::<Text>()).maybe(),
+            (&world.read_storage::<Shape>()).maybe(),
+            (&world.read_storage::<ParticleEmitter>()).maybe(),
+        )
+            .guided_join(&self.sorted_entities)
+        {
+            if current_layer != Some(render.layer) {
+                if transformed {
+                    backend.pop_transform()?;
+                }
+                current_layer = Some(render.layer);
+
+                // Unknown layers are drawn like a plain world layer.
+                let (space, parallax, visible) = layers
+                    .as_ref()
+                    .and_then(|layers| layers.get(render.layer))
+                    .map_or((LayerSpace::World, 1.0, true), |layer| {
+                        (layer.space, layer.parallax, layer.visible)
+                    });
+                layer_visible = visible;
+                transformed = false;
+
+                if let (LayerSpace::World, Some(camera)) = (space, &camera) {
+                    backend.push_transform(camera.parallax_transform(parallax))?;
+                    transformed = true;
+                }
+            }
+
+            if !render.visible || !layer_visible {
+                continue;
+            }
+
+            // Images draw their whole texture, sprites a frame of their sheet.
+            let texture = if let Some(image) = image {
+                Some((&image.image, graphics::Rect::one()))
+            } else if let Some(sprite) = sprite {
+                sprite
+                    .sheet
+                    .src(sprite.frame)
+                    .map(|src| (sprite.sheet.image(), src))
+            } else {
+                None
+            };
+
+            let blend = style.and_then(|style| style.blend);
+
+            // Particles are drawn behind the entity they are emitted from,
+            // as a white pixel if the emitter has no image.
+            if let Some(emitter) = emitter {
+                let (image_w, image_h) = emitter.image.as_ref().map_or((1.0, 1.0), |image| {
+                    (image.width() as f32, image.height() as f32)
+                });
+
+                for particle in emitter.particles() {
+                    let particle_size = emitter.size(particle);
+                    let color = emitter.color(particle);
+                    let draw_params = graphics::DrawParam::new()
+                        .dest(Point2 {
+                            x: particle.x - particle_size / 2.0,
+                            y: particle.y - particle_size / 2.0,
+                        })
+                        .scale(mint::Vector2 {
+                            x: particle_size / image_w,
+                            y: particle_size / image_h,
+                        })
+                        .color(style.map_or(color, |style| style.apply(color)));
+                    backend.draw_image(emitter.image.as_ref(), draw_params, blend)?;
+                }
+            }
+
+            if let Some((texture, src)) = texture {
+                let mut draw_params = graphics::DrawParam::new()
+                    .src(src)
+                    .dest(Point2 { x: pos.x, y: pos.y });
+
+                let mut offset = Point2 { x: 0.0, y: 0.0 };
+                if let Some(rotation) = rotation {
+                    offset = Point2 {
+                        x: rotation.x,
+                        y: rotation.y,
+                    };
+                    draw_params = draw_params.rotation(rotation.deg);
+                }
+                if let Some(style) = style {
+                    // Mirroring the offset keeps a flipped image in place.
+                    if style.flip_x {
+                        offset.x = 1.0 - offset.x;
+                    }
+                    if style.flip_y {
+                        offset.y = 1.0 - offset.y;
+                    }
+                    draw_params = draw_params
+                        .color(style.apply(graphics::WHITE))
+                        .scale(style.signed_scale());
+                }
+                draw_params = draw_params.offset(offset);
+
+                backend.draw_image(Some(texture), draw_params, blend)?;
+            } else if let Some(text) = text {
+                draw_text(
+                    backend,
+                    &mut self.text_cache,
+                    entity,
+                    text,
+                    pos,
+                    size,
+                    style,
+                )?;
+            } else if let Some(shape) = shape {
+                let mut draw_params = graphics::DrawParam::new()
+                    .dest(Point2 { x: pos.x, y: pos.y })
+                    .color(style.map_or(shape.color, |style| style.apply(shape.color)));
+
+                if let Some(rotation) = rotation {
+                    draw_params = draw_params.rotation(rotation.deg);
+                }
+                if let Some(style) = style {
+                    draw_params = draw_params.scale(style.signed_scale());
+                }
+
+                backend.draw_shape(shape, draw_params, blend)?;
+            }
+        }
+        if transformed {
+            backend.pop_transform()?;
+        }
+
+        if let Some(overlay) = world.try_fetch::<DebugOverlay>() {
+            if overlay.enabled {
+                overlay.draw(backend, world)?;
+            }
+        }
+
+        if self.show_fps {
+            self.draw_fps(backend)?;
+        }
+
+        backend.finish()
+    }
+
+    fn draw_fps(&self, backend: &mut dyn RenderBackend) -> GameResult {
+        let fps_display =
+            graphics::Text::new((format!("FPS: {:.1}", self.fps), self.fps_font, 8.0));
+
+        backend.queue_text(
+            &fps_display,
+            mint::Point2 { x: 10.0, y: 10.0 },
+            graphics::Color::from_rgb(0, 255, 0),
+        );
+        backend.draw_queued_text(graphics::DrawParam::default(), None)
+    }
+}
+
+/// The directions an outline is drawn in.
+const OUTLINE_OFFSETS: [(f32, f32); 8] = [
+    (-1.0, -1.0),
+    (0.0, -1.0),
+    (1.0, -1.0),
+    (-1.0, 0.0),
+    (1.0, 0.0),
+    (-1.0, 1.0),
+    (0.0, 1.0),
+    (1.0, 1.0),
+];
+
+/// Lays out the text within the box of `pos` and `size`, or takes its
+/// layout from the cache, and draws it with its effect and style.
+fn draw_text(
+    backend: &mut dyn RenderBackend,
+    text_cache: &mut TextCache,
+    entity: Entity,
+    text: &Text,
+    pos: &Position,
+    size: Option<&Size>,
+    style: Option<&Style>,
+) -> GameResult {
+    // The text is laid out unscaled, so the box is scaled down to match.
+    let (scale_x, scale_y) = style.map_or((1.0, 1.0), |style| (style.scale.x, style.scale.y));
+    let (origin_x, box_w) = match (size, &text.align) {
+        (_, Alignment::Left) => (pos.x, size.map_or(f32::INFINITY, |size| size.w)),
+        (Some(size), _) => (pos.x, size.w),
+        (None, _) => (0.0, backend.screen_coordinates().w),
+    };
+    let box_w = box_w / scale_x;
+    let box_h = size.map_or(f32::INFINITY, |size| size.h) / scale_y;
+
+    let wrap_width = if text.wrap && box_w.is_finite() {
+        Some(box_w)
+    } else {
+        None
+    };
+    let layout = text_cache.get(backend, entity.id(), text, wrap_width, style);
+
+    let offset_y = match text.valign {
+        _ if !box_h.is_finite() => 0.0,
+        VerticalAlignment::Top => 0.0,
+        VerticalAlignment::Middle => ((box_h - layout.height) / 2.0).floor(),
+        VerticalAlignment::Bottom => box_h - layout.height,
+    };
+
+    let apply = |color: graphics::Color| style.map_or(color, |style| style.apply(color));
+    let mut extent_x: f32 = 0.0;
+    for line in &layout.lines {
+        let x = match text.align {
+            Alignment::Left => 0.0,
+            Alignment::Right => box_w - line.width,
+            Alignment::Centered => (box_w / 2.0) - (line.width / 2.0).floor(),
+        };
+        let y = offset_y + line.y;
+        extent_x = extent_x.max(x + line.width);
+
+        match &text.effect {
+            Some(TextEffect::Shadow { offset, color }) => {
+                let dest = Point2 {
+                    x: x + offset.x,
+                    y: y + offset.y,
+                };
+                backend.queue_text(&line.plain(text), dest, apply(*color));
+            }
+            Some(TextEffect::Outline { width, color }) => {
+                for (dx, dy) in OUTLINE_OFFSETS.iter() {
+                    let dest = Point2 {
+                        x: x + dx * width,
+                        y: y + dy * width,
+                    };
+                    backend.queue_text(&line.plain(text), dest, apply(*color));
+                }
+            }
+            None => {}
+        }
+        backend.queue_text(&line.colored(text), Point2 { x, y }, apply(text.color));
+    }
+
+    // Mirroring happens around the origin, so flipped text is moved back
+    // into its box.
+    let mut dest = Point2 {
+        x: origin_x,
+        y: pos.y,
+    };
+    let mut param = graphics::DrawParam::new();
+    if let Some(style) = style {
+        if style.flip_x {
+            dest.x += extent_x * scale_x;
+        }
+        if style.flip_y {
+            dest.y += (offset_y + layout.height) * scale_y;
+        }
+        param = param.scale(style.signed_scale());
+    }
+    backend.draw_queued_text(param.dest(dest), style.and_then(|style| style.blend))
+}
+
+#[cfg(test)]
+mod tests {
+    use specs::Builder;
+
+    use super::*;
+    use crate::render::{DrawCommand, RecordingBackend};
+
+    fn create_world() -> World {
+        let mut world = World::new();
+        crate::render::register(&mut world);
+        world.insert(
+            RenderLayers::new()
+                .world("back", 0.5)
+                .world("front", 1.0)
+                .screen("ui"),
+        );
+        world
+    }
+
+    fn render(layer: &'static str) -> Render {
+        Render {
+            visible: true,
+            layer,
+        }
+    }
+
+    fn create_shape(world: &mut World, layer: &'static str, w: f32) -> Entity {
+        world
+            .create_entity()
+            .with(render(layer))
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(Shape::rectangle(w, 1.0, graphics::WHITE))
+            .build()
+    }
+
+    fn create_emitter(world: &mut World, particles: usize, style: Option<Style>) -> Entity {
+        let mut emitter = ParticleEmitter::new(1.0..1.0);
+        emitter.burst(particles);
+        emitter.update(0.0, 0.0, 0.0);
+
+        let builder = world
+            .create_entity()
+            .with(render("front"))
+            .with(Position { x: 0.0, y: 0.0 })
+            .with(emitter);
+        match style {
+            Some(style) => builder.with(style).build(),
+            None => builder.build(),
+        }
+    }
+
+    fn record(system: &mut RenderSystem, world: &World) -> Vec<DrawCommand> {
+        let mut backend = RecordingBackend::new(100.0, 100.0);
+        system.draw_with(&mut backend, world).unwrap();
+        backend.take_commands()
+    }
+
+    /// Returns the widths of the recorded rectangles in draw order.
+    fn rectangle_widths(commands: &[DrawCommand]) -> Vec<f32> {
+        commands
+            .iter()
+            .filter_map(|command| match command {
+                DrawCommand::Shape { shape, .. } => match shape.kind {
+                    ShapeKind::Rectangle { w, .. } => Some(w),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draws_layers_from_back_to_front() {
+        let mut world = create_world();
+        // The system only sees entities created after it.
+        let mut system = RenderSystem::new(&mut world).with_show_fps(false);
+        create_shape(&mut world, "ui", 3.0);
+        create_shape(&mut world, "front", 2.0);
+        create_shape(&mut world, "back", 1.0);
+        create_shape(&mut world, "back", 4.0);
+
+        let commands = record(&mut system, &world);
+        assert_eq!(rectangle_widths(&commands), vec![1.0, 4.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn draws_world_layers_through_the_camera() {
+        let mut world = create_world();
+        world.insert(Camera::new(100.0, 100.0));
+        let mut system = RenderSystem::new(&mut world).with_show_fps(false);
+        create_shape(&mut world, "back", 1.0);
+        create_shape(&mut world, "front", 2.0);
+        create_shape(&mut world, "ui", 3.0);
+
+        let commands = record(&mut system, &world);
+        let camera = world.read_resource::<Camera>();
+        assert_eq!(commands.len(), 7);
+        assert!(
+            matches!(&commands[0], DrawCommand::PushTransform(t) if *t == camera.parallax_transform(0.5))
+        );
+        assert!(matches!(&commands[1], DrawCommand::Shape { .. }));
+        assert!(matches!(&commands[2], DrawCommand::PopTransform));
+        assert!(matches!(&commands[3], DrawCommand::PushTransform(t) if *t == camera.transform()));
+        assert!(matches!(&commands[4], DrawCommand::Shape { .. }));
+        assert!(matches!(&commands[5], DrawCommand::PopTransform));
+        assert!(matches!(&commands[6], DrawCommand::Shape { .. }));
+    }
+
+    #[test]
+    fn skips_hidden_entities_and_layers() {
+        let mut world = create_world();
+        let mut system = RenderSystem::new(&mut world).with_show_fps(false);
+        create_shape(&mut world, "front", 1.0);
+        let hidden = create_shape(&mut world, "front", 2.0);
+        create_shape(&mut world, "back", 3.0);
+        world
+            .write_storage::<Render>()
+            .get_mut(hidden)
+            .unwrap()
+            .visible = false;
+        world
+            .write_resource::<RenderLayers>()
+            .set_visible("back", false);
+
+        let commands = record(&mut system, &world);
+        assert_eq!(rectangle_widths(&commands), vec![1.0]);
+    }
+
+    #[test]
+    fn batches_consecutive_images_with_the_same_blend_mode() {
+        let mut world = create_world();
+        let mut system = RenderSystem::new(&mut world).with_show_fps(false);
+        create_emitter(&mut world, 3, None);
+        create_emitter(&mut world, 2, None);
+        create_shape(&mut world, "front", 1.0);
+        create_emitter(
+            &mut world,
+            1,
+            Some(Style {
+                blend: Some(graphics::BlendMode::Add),
+                ..Default::default()
+            }),
+        );
+        create_emitter(&mut world, 1, None);
+
+        let batches: Vec<(usize, Option<graphics::BlendMode>)> = record(&mut system, &world)
+            .iter()
+            .map(|command| match command {
+                DrawCommand::Images { params, blend, .. } => (params.len(), *blend),
+                _ => (0, None),
+            })
+            .collect();
+        assert_eq!(
+            batches,
+            vec![
+                (5, None),
+                (0, None),
+                (1, Some(graphics::BlendMode::Add)),
+                (1, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn aligns_text_within_its_box() {
+        let mut world = create_world();
+        let mut system = RenderSystem::new(&mut world).with_show_fps(false);
+        for align in &[Alignment::Left, Alignment::Centered, Alignment::Right] {
+            world
+                .create_entity()
+                .with(render("ui"))
+                .with(Position { x: 5.0, y: 7.0 })
+                .with(Size {
+                    w: 100.0,
+                    h: f32::INFINITY,
+                })
+                .with(Text {
+                    text: String::from("abcd"),
+                    font_size: 10.0,
+                    align: align.clone(),
+                    ..Default::default()
+                })
+                .build();
+        }
+        // Without a size, centered text is centered on the screen.
+        world
+            .create_entity()
+            .with(render("ui"))
+            .with(Position { x: 5.0, y: 7.0 })
+            .with(Text {
+                text: String::from("abcd"),
+                font_size: 10.0,
+                align: Alignment::Centered,
+                ..Default::default()
+            })
+            .build();
+
+        // Every glyph is half its font size wide, so the text is 20 wide.
+        let lines: Vec<(String, f32, f32)> = record(&mut system, &world)
+            .into_iter()
+            .filter_map(|command| match command {
+                DrawCommand::Text {
+                    text, dest, params, ..
+                } => {
+                    assert_eq!(params.dest.y, 7.0);
+                    Some((text, dest.x, params.dest.x))
+                }
+                _ => None,
+            })
+            .collect();
+        let abcd = String::from("abcd");
+        assert_eq!(
+            lines,
+            vec![
+                (abcd.clone(), 0.0, 5.0),
+                (abcd.clone(), 40.0, 5.0),
+                (abcd.clone(), 80.0, 5.0),
+                (abcd, 40.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn draws_the_debug_overlay_and_fps_through_the_backend() {
+        let mut world = create_world();
+        world.insert(Camera::new(100.0, 100.0));
+        let mut overlay = DebugOverlay::new();
+        overlay.enabled = true;
+        world.insert(overlay);
+        let mut system = RenderSystem::new(&mut world);
+        world
+            .create_entity()
+            .with(render("back"))
+            .with(Position { x: 4.0, y: 6.0 })
+            .with(Size { w: 8.0, h: 2.0 })
+            .build();
+
+        let commands = record(&mut system, &world);
+        let camera = world.read_resource::<Camera>();
+        let back = camera.parallax_transform(0.5);
+        // The entity itself draws nothing, but still enters its layer.
+        assert_eq!(commands.len(), 7);
+        assert!(matches!(&commands[0], DrawCommand::PushTransform(t) if *t == back));
+        assert!(matches!(&commands[1], DrawCommand::PopTransform));
+        assert!(matches!(&commands[2], DrawCommand::PushTransform(t) if *t == back));
+        match &commands[3] {
+            DrawCommand::Shape { shape, params, .. } => {
+                assert_eq!(shape.kind, ShapeKind::Rectangle { w: 8.0, h: 2.0 });
+                assert_eq!((params.dest.x, params.dest.y), (4.0, 6.0));
+            }
+            command => panic!("expected the box, got {:?}", command),
+        }
+        assert!(matches!(&commands[4], DrawCommand::Text { text, .. } if text == "0"));
+        assert!(matches!(&commands[5], DrawCommand::PopTransform));
+        assert!(matches!(&commands[6], DrawCommand::Text { text, .. } if text.starts_with("FPS:")));
+    }
+}
@@ -0,0 +1,226 @@
+use ggez::graphics;
+use specs::world::Index;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::backend::RenderBackend;
+use super::components::{Style, Text};
+
+/// A line of laid out text, relative to the top left of the layout. Only
+/// the ranges of the line are kept, as a `graphics::Text` cannot be shared
+/// between threads; the text is built from them when it is drawn.
+pub struct TextLine {
+    pub range: Range<usize>,
+    /// The fragments of the line, with the colour of their span if any.
+    pub fragments: Vec<(Range<usize>, Option<graphics::Color>)>,
+    pub width: f32,
+    pub y: f32,
+}
+
+impl TextLine {
+    /// Builds the line of `text` with the colours of its spans.
+    pub fn colored(&self, text: &Text) -> graphics::Text {
+        let mut line = graphics::Text::default();
+        for (range, color) in &self.fragments {
+            let mut fragment = graphics::TextFragment::from((
+                &text.text[range.clone()],
+                text.font,
+                text.font_size,
+            ));
+            if let Some(color) = color {
+                fragment = fragment.color(*color);
+            }
+            line.add(fragment);
+        }
+        line
+    }
+
+    /// Builds the line of `text` without colours, for shadows and outlines.
+    pub fn plain(&self, text: &Text) -> graphics::Text {
+        graphics::Text::new((&text.text[self.range.clone()], text.font, text.font_size))
+    }
+}
+
+/// A `Text` component broken into lines.
+pub struct TextLayout {
+    pub lines: Vec<TextLine>,
+    pub height: f32,
+}
+
+impl TextLayout {
+    /// Lays out `text`, wrapping it at `wrap_width` if given. The colours of
+    /// the spans are tinted by `style`.
+    pub fn new(
+        backend: &mut dyn RenderBackend,
+        text: &Text,
+        wrap_width: Option<f32>,
+        style: Option<&Style>,
+    ) -> Self {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for paragraph in text.text.split('\n') {
+            let end = start + paragraph.len();
+            wrap(backend, text, start..end, wrap_width, &mut ranges);
+            start = end + 1;
+        }
+
+        let line_height = text.font_size * text.line_spacing;
+        let lines: Vec<TextLine> = ranges
+            .into_iter()
+            .enumerate()
+            .map(|(index, range)| {
+                let mut line = TextLine {
+                    fragments: fragments(text, range.clone(), style),
+                    range,
+                    width: 0.0,
+                    y: index as f32 * line_height,
+                };
+                line.width = backend.text_width(&line.colored(text));
+                line
+            })
+            .collect();
+
+        let height = match lines.last() {
+            Some(line) => line.y + text.font_size,
+            None => 0.0,
+        };
+
+        Self { lines, height }
+    }
+}
+
+struct CachedLayout {
+    layout: TextLayout,
+    wrap_width: Option<f32>,
+    tint: Option<graphics::Color>,
+}
+
+/// Keeps the layout of every text entity between frames. Layouts are
+/// rebuilt when the `RenderSystem` sees the `Text` component change, or when
+/// the wrap width or the tint of the entity changed.
+#[derive(Default)]
+pub struct TextCache {
+    layouts: HashMap<Index, CachedLayout>,
+}
+
+impl std::fmt::Debug for TextCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextCache")
+            .field("layouts", &self.layouts.len())
+            .finish()
+    }
+}
+
+impl TextCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn invalidate(&mut self, id: Index) {
+        self.layouts.remove(&id);
+    }
+
+    /// Returns the layout of the text of entity `id`, laying it out if needed.
+    pub fn get(
+        &mut self,
+        backend: &mut dyn RenderBackend,
+        id: Index,
+        text: &Text,
+        wrap_width: Option<f32>,
+        style: Option<&Style>,
+    ) -> &TextLayout {
+        let tint = style.map(|style| style.apply(graphics::WHITE));
+        let cached = self.layouts.entry(id).or_insert_with(|| CachedLayout {
+            layout: TextLayout::new(backend, text, wrap_width, style),
+            wrap_width,
+            tint,
+        });
+        if cached.wrap_width != wrap_width || cached.tint != tint {
+            *cached = CachedLayout {
+                layout: TextLayout::new(backend, text, wrap_width, style),
+                wrap_width,
+                tint,
+            };
+        }
+        &cached.layout
+    }
+}
+
+fn measure(backend: &mut dyn RenderBackend, text: &Text, s: &str) -> f32 {
+    backend.text_width(&graphics::Text::new((s, text.font, text.font_size)))
+}
+
+/// Breaks the paragraph within `range` into lines no wider than `wrap_width`,
+/// unless a single word is wider.
+fn wrap(
+    backend: &mut dyn RenderBackend,
+    text: &Text,
+    range: Range<usize>,
+    wrap_width: Option<f32>,
+    lines: &mut Vec<Range<usize>>,
+) {
+    let max_width = match wrap_width {
+        Some(width) => width,
+        None => {
+            lines.push(range);
+            return;
+        }
+    };
+
+    let source = &text.text;
+    let mut line_start = range.start;
+    let mut line_end = range.start;
+    let mut word_start = range.start;
+    loop {
+        let word_end = source[word_start..range.end]
+            .find(' ')
+            .map_or(range.end, |i| word_start + i);
+        if line_end > line_start
+            && measure(backend, text, &source[line_start..word_end]) > max_width
+        {
+            lines.push(line_start..line_end);
+            line_start = word_start;
+        }
+        line_end = word_end;
+
+        if word_end >= range.end {
+            break;
+        }
+        word_start = word_end + 1;
+    }
+    lines.push(line_start..line_end);
+}
+
+/// Splits the line within `range` into a fragment for every change of
+/// colour.
+fn fragments(
+    text: &Text,
+    range: Range<usize>,
+    style: Option<&Style>,
+) -> Vec<(Range<usize>, Option<graphics::Color>)> {
+    let mut cuts = vec![range.start, range.end];
+    for span in &text.spans {
+        for cut in &[span.range.start, span.range.end] {
+            if *cut > range.start && *cut < range.end {
+                cuts.push(*cut);
+            }
+        }
+    }
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut fragments = Vec::new();
+    for cut in cuts.windows(2) {
+        if text.text.get(cut[0]..cut[1]).is_none() {
+            continue;
+        }
+        let span = text
+            .spans
+            .iter()
+            .rev()
+            .find(|span| span.range.start <= cut[0] && cut[1] <= span.range.end);
+        let color = span.map(|span| style.map_or(span.color, |style| style.apply(span.color)));
+        fragments.push((cut[0]..cut[1], color));
+    }
+    fragments
+}
@@ -0,0 +1,56 @@
+//! Finds the resources of a game, whether it runs with cargo, as a binary
+//! shipped with its resources, or with its resources embedded.
+use std::borrow::Cow;
+use std::env;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use ggez::{ContextBuilder, GameError, GameResult};
+
+/// Returns the loose resources directory of the game: `resources` in the
+/// crate when it runs with cargo, or next to the executable otherwise.
+pub fn resource_dir() -> PathBuf {
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        return PathBuf::from(manifest_dir).join("resources");
+    }
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("resources")))
+        .unwrap_or_else(|| PathBuf::from("./resources"))
+}
+
+/// Mounts the resources of the game, which are searched in this order:
+/// - the `resources` directory and `resources.zip` next to the executable,
+///   which ggez always mounts,
+/// - `resource_dir`, usually the one `resource_dir()` returns,
+/// - the `packed` zip archive embedded in the binary, if any.
+///
+/// So loose files override packed ones, which lets players mod a game.
+pub fn mount<B>(cb: ContextBuilder, resource_dir: &Path, packed: Option<B>) -> ContextBuilder
+where
+    B: Into<Cow<'static, [u8]>>,
+{
+    let cb = cb.add_resource_path(resource_dir);
+    match packed {
+        Some(packed) => cb.add_zipfile_bytes(packed),
+        None => cb,
+    }
+}
+
+/// Reads the file at `path`, like `/images/bird.png`, from a `packed`
+/// archive, looking it up by the same name ggez does once the archive is
+/// mounted. It needs no context, so archives can be checked without a game.
+pub fn read_packed(packed: &[u8], path: &str) -> GameResult<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(packed)).map_err(|e| {
+        GameError::ResourceLoadError(format!("Unable to open the packed resources: {}", e))
+    })?;
+    let mut file = archive.by_name(path).map_err(|e| {
+        GameError::ResourceLoadError(format!(
+            "Unable to read {} from the packed resources: {}",
+            path, e
+        ))
+    })?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
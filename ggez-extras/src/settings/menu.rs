@@ -0,0 +1,191 @@
+use ggez::{graphics, Context, GameResult};
+use specs::{Builder, Entity, World, WorldExt};
+
+use super::Settings;
+use crate::audio::{AudioManager, Bus};
+use crate::render::{Alignment, Position, Render, Size, Text, TextSpan};
+
+/// The window sizes the menu switches between.
+const WINDOW_SIZES: [(f32, f32); 5] = [
+    (960.0, 540.0),
+    (1280.0, 720.0),
+    (1280.0, 780.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+];
+
+const SELECTED_COLOR: graphics::Color = graphics::Color {
+    r: 1.0,
+    g: 0.85,
+    b: 0.2,
+    a: 1.0,
+};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Item {
+    WindowSize,
+    Fullscreen,
+    ShowFps,
+    Volume,
+}
+
+const ITEMS: [Item; 4] = [
+    Item::WindowSize,
+    Item::Fullscreen,
+    Item::ShowFps,
+    Item::Volume,
+];
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Lists the `Settings` resource in a text entity, one setting per line,
+/// and changes the selected one. Changes apply to the running game right
+/// away, the FPS counter as soon as the game reads the resource for the
+/// next frame; the game saves the settings when it is done with the menu.
+#[derive(Debug)]
+pub struct SettingsMenu {
+    layer: &'static str,
+    font: graphics::Font,
+    font_size: f32,
+    width: f32,
+    selected: usize,
+    text: Option<Entity>,
+}
+
+impl SettingsMenu {
+    /// Creates a menu drawn on `layer`, centered on a screen `width` wide.
+    pub fn new(layer: &'static str, font: graphics::Font, font_size: f32, width: f32) -> Self {
+        Self {
+            layer,
+            font,
+            font_size,
+            width,
+            selected: 0,
+            text: None,
+        }
+    }
+
+    /// Creates the text of the menu at height `y` and returns its entity.
+    pub fn create_entities(&mut self, world: &mut World, y: f32) -> Vec<Entity> {
+        let text = world
+            .create_entity()
+            .with(Render {
+                visible: true,
+                layer: self.layer,
+            })
+            .with(Text {
+                font: self.font,
+                font_size: self.font_size,
+                color: graphics::WHITE,
+                align: Alignment::Centered,
+                line_spacing: 1.5,
+                ..Default::default()
+            })
+            .with(Size {
+                w: self.width,
+                h: f32::INFINITY,
+            })
+            .with(Position { x: 0.0, y })
+            .build();
+
+        self.text = Some(text);
+        self.refresh(world);
+        vec![text]
+    }
+
+    pub fn select_next(&mut self, world: &World) {
+        self.selected = (self.selected + 1) % ITEMS.len();
+        self.refresh(world);
+    }
+
+    pub fn select_previous(&mut self, world: &World) {
+        self.selected = (self.selected + ITEMS.len() - 1) % ITEMS.len();
+        self.refresh(world);
+    }
+
+    /// Changes the selected setting one step up for a positive `step`, or
+    /// down for a negative one, and applies it.
+    pub fn change(&mut self, world: &World, ctx: &mut Context, step: i32) -> GameResult {
+        {
+            let mut settings = world.write_resource::<Settings>();
+            match ITEMS[self.selected] {
+                Item::WindowSize => {
+                    let (width, height) = next_window_size(&settings, step);
+                    settings.window_width = width;
+                    settings.window_height = height;
+                    graphics::set_mode(ctx, settings.window_mode())?;
+                }
+                Item::Fullscreen => {
+                    settings.fullscreen = !settings.fullscreen;
+                    graphics::set_mode(ctx, settings.window_mode())?;
+                }
+                Item::ShowFps => settings.show_fps = !settings.show_fps,
+                Item::Volume => {
+                    let mut audio = world.write_resource::<AudioManager>();
+                    let volume = (audio.volume(Bus::Master) * 10.0).round() + step.signum() as f32;
+                    audio.set_volume(Bus::Master, volume / 10.0);
+                    settings.audio = audio.settings().clone();
+                }
+            }
+        }
+        self.refresh(world);
+        Ok(())
+    }
+
+    /// Writes the current settings into the text, with the selected one
+    /// highlighted.
+    fn refresh(&self, world: &World) {
+        let settings = world.read_resource::<Settings>();
+        let mut text = String::new();
+        let mut spans = Vec::new();
+        for (index, item) in ITEMS.iter().enumerate() {
+            let line = match item {
+                Item::WindowSize => format!(
+                    "Window size: {} x {}",
+                    settings.window_width, settings.window_height
+                ),
+                Item::Fullscreen => format!("Fullscreen: {}", on_off(settings.fullscreen)),
+                Item::ShowFps => format!("Show FPS: {}", on_off(settings.show_fps)),
+                Item::Volume if settings.audio.master.muted => String::from("Volume: muted"),
+                Item::Volume => format!("Volume: {:.0}%", settings.audio.master.volume * 100.0),
+            };
+
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            if index == self.selected {
+                spans.push(TextSpan {
+                    range: text.len()..text.len() + line.len(),
+                    color: SELECTED_COLOR,
+                });
+            }
+            text.push_str(&line);
+        }
+
+        let mut texts = world.write_storage::<Text>();
+        if let Some(component) = self.text.and_then(|entity| texts.get_mut(entity)) {
+            component.text = text;
+            component.spans = spans;
+        }
+    }
+}
+
+/// Returns the window size `step` places from the current one, wrapping
+/// around. A size that is not in the list counts as the next larger one.
+fn next_window_size(settings: &Settings, step: i32) -> (f32, f32) {
+    let area = settings.window_width * settings.window_height;
+    let current = WINDOW_SIZES
+        .iter()
+        .position(|(w, h)| w * h >= area)
+        .unwrap_or(WINDOW_SIZES.len() - 1);
+    let exact = WINDOW_SIZES[current] == (settings.window_width, settings.window_height);
+    let step = if !exact && step > 0 { step - 1 } else { step };
+    let len = WINDOW_SIZES.len() as i32;
+    WINDOW_SIZES[(current as i32 + step).rem_euclid(len) as usize]
+}
@@ -0,0 +1,203 @@
+//! User settings that are kept in the user config directory between runs.
+//! They are read before the context is built, so the window is created
+//! with them.
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use ggez::{conf, GameError, GameResult};
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::audio::AudioSettings;
+
+mod menu;
+
+pub use menu::SettingsMenu;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Where earlier versions kept the audio settings, next to the settings
+/// file. They are taken over until the settings are saved.
+const AUDIO_SETTINGS_FILE: &str = "audio.json";
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window_width: f32,
+    pub window_height: f32,
+    pub fullscreen: bool,
+    /// Only takes effect when the game starts, so the settings menu leaves
+    /// it alone.
+    pub vsync: bool,
+    pub show_fps: bool,
+    pub audio: AudioSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            window_width: 1280.0,
+            window_height: 720.0,
+            fullscreen: false,
+            vsync: true,
+            show_fps: true,
+            audio: AudioSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn with_window_size(mut self, width: f32, height: f32) -> Self {
+        self.window_width = width;
+        self.window_height = height;
+        self
+    }
+
+    /// Returns where the settings of a game are stored: the user config
+    /// directory ggez uses for the `game_id` and `author` of the context.
+    pub fn path(game_id: &str, author: &str) -> Option<PathBuf> {
+        ProjectDirs::from("", author, game_id).map(|dirs| dirs.config_dir().join(SETTINGS_FILE))
+    }
+
+    /// Loads the saved settings, or `default` if there are none or they
+    /// cannot be read.
+    pub fn load(game_id: &str, author: &str, default: Self) -> Self {
+        match Self::path(game_id, author) {
+            Some(path) => Self::load_from(&path, default),
+            None => default,
+        }
+    }
+
+    /// Loads the settings saved at `path`. Without them, `default` keeps the
+    /// audio settings an earlier version saved next to them, if any.
+    fn load_from(path: &Path, mut default: Self) -> Self {
+        if !path.exists() {
+            let audio_path = path.with_file_name(AUDIO_SETTINGS_FILE);
+            if audio_path.exists() {
+                match read_json(&audio_path) {
+                    Ok(audio) => default.audio = audio,
+                    Err(e) => warn!("Unable to load audio settings from {:?}: {}", audio_path, e),
+                }
+            }
+            return default;
+        }
+
+        match read_json(path) {
+            Ok(settings) => settings,
+            Err(e) => {
+                warn!("Unable to load settings from {:?}: {}", path, e);
+                default
+            }
+        }
+    }
+
+    pub fn save(&self, game_id: &str, author: &str) -> GameResult {
+        let path = Self::path(game_id, author).ok_or_else(|| {
+            GameError::FilesystemError(String::from("There is no user config directory"))
+        })?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = File::create(&path)?;
+        serde_json::to_writer_pretty(file, self).map_err(|e| {
+            GameError::FilesystemError(format!("Unable to save settings to {:?}: {}", path, e))
+        })
+    }
+
+    /// Returns the resizable window mode the settings ask for.
+    pub fn window_mode(&self) -> conf::WindowMode {
+        let fullscreen_type = if self.fullscreen {
+            conf::FullscreenType::Desktop
+        } else {
+            conf::FullscreenType::Windowed
+        };
+        conf::WindowMode::default()
+            .dimensions(self.window_width, self.window_height)
+            .fullscreen_type(fullscreen_type)
+            .resizable(true)
+    }
+
+    /// Returns `setup` with the vsync of the settings.
+    pub fn window_setup(&self, setup: conf::WindowSetup) -> conf::WindowSetup {
+        setup.vsync(self.vsync)
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    serde_json::from_reader(file).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the path of the settings file in an empty directory of its
+    /// own, named after the test.
+    fn settings_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ggez-extras-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(SETTINGS_FILE)
+    }
+
+    fn muted_audio() -> AudioSettings {
+        let mut audio = AudioSettings::default();
+        audio.master.volume = 0.3;
+        audio.music.muted = true;
+        audio
+    }
+
+    #[test]
+    fn loads_the_default_without_a_file() {
+        let path = settings_path("default");
+        let default = Settings::default().with_window_size(960.0, 540.0);
+
+        assert_eq!(Settings::load_from(&path, default.clone()), default);
+    }
+
+    #[test]
+    fn loads_saved_settings() {
+        let path = settings_path("saved");
+        let saved = Settings {
+            fullscreen: true,
+            audio: muted_audio(),
+            ..Settings::default()
+        };
+        serde_json::to_writer(File::create(&path).unwrap(), &saved).unwrap();
+
+        assert_eq!(Settings::load_from(&path, Settings::default()), saved);
+    }
+
+    #[test]
+    fn takes_over_the_audio_settings_of_earlier_versions() {
+        let path = settings_path("migrate");
+        let audio_path = path.with_file_name(AUDIO_SETTINGS_FILE);
+        serde_json::to_writer(File::create(&audio_path).unwrap(), &muted_audio()).unwrap();
+
+        let settings = Settings::load_from(&path, Settings::default());
+
+        assert_eq!(
+            settings,
+            Settings {
+                audio: muted_audio(),
+                ..Settings::default()
+            }
+        );
+    }
+
+    #[test]
+    fn prefers_the_settings_file_over_earlier_audio_settings() {
+        let path = settings_path("prefer");
+        serde_json::to_writer(File::create(&path).unwrap(), &Settings::default()).unwrap();
+        let audio_path = path.with_file_name(AUDIO_SETTINGS_FILE);
+        serde_json::to_writer(File::create(&audio_path).unwrap(), &muted_audio()).unwrap();
+
+        assert_eq!(
+            Settings::load_from(&path, Settings::default()),
+            Settings::default()
+        );
+    }
+}
@@ -0,0 +1,5 @@
+/// The fixed time step, in seconds, that systems advance the game by.
+#[derive(Clone, Debug, Default)]
+pub struct DeltaTime {
+    pub delta: f32,
+}
@@ -0,0 +1,111 @@
+//! Packs the resources directory of a game into a zip archive, which ggez
+//! mounts like a directory.
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Seek, Write};
+use std::path::Path;
+
+use zip::write::{FileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+/// Packs every file below `dir` into a zip archive at `out`, named by its
+/// path relative to `dir` with a leading slash, which is the name ggez
+/// looks a file up by in a mounted archive.
+pub fn pack<P: AsRef<Path>, Q: AsRef<Path>>(dir: P, out: Q) -> io::Result<()> {
+    let mut zip = ZipWriter::new(File::create(out)?);
+    add_dir(&mut zip, dir.as_ref(), dir.as_ref())?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// Runs the build script of a game: packs its `resources` directory into
+/// `resources.zip` in `OUT_DIR` when the game is built with the
+/// `embed-resources` feature, for `include_bytes!` to embed in the binary.
+pub fn build_script() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBED_RESOURCES").is_none() {
+        return;
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("ERROR: Not run from a build script.");
+    let archive = Path::new(&out_dir).join("resources.zip");
+    pack("resources", archive).expect("ERROR: Unable to pack the resources.");
+    println!("cargo:rerun-if-changed=resources");
+}
+
+fn add_dir<W: Write + Seek>(zip: &mut ZipWriter<W>, root: &Path, dir: &Path) -> io::Result<()> {
+    // Sorted, so packing the same files gives the same archive.
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            add_dir(zip, root, &path)?;
+            continue;
+        }
+
+        let name = path
+            .strip_prefix(root)
+            .expect("ERROR: Packed a file outside of the resources.")
+            .components()
+            .map(|component| format!("/{}", component.as_os_str().to_string_lossy()))
+            .collect::<String>();
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file(name, options)?;
+        io::copy(&mut File::open(&path)?, zip)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    use ggez_extras::resources::read_packed;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    const FILES: [(&str, &[u8]); 3] = [
+        ("assets.json", b"{ \"images\": {} }"),
+        ("images/bird.png", &[0x89, b'P', b'N', b'G', 0, 255]),
+        ("sounds/sfx/jump.wav", b"RIFF"),
+    ];
+
+    /// Writes `FILES` into an empty directory of its own and returns it.
+    fn create_resources() -> PathBuf {
+        let dir = env::temp_dir().join(format!("pack-resources-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (path, bytes) in FILES.iter() {
+            let file = dir.join(path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(file, bytes).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn packs_files_to_be_read_back_by_their_resource_path() {
+        let dir = create_resources();
+        let archive = dir.with_extension("zip");
+
+        pack(&dir, &archive).unwrap();
+
+        let packed = fs::read(&archive).unwrap();
+        let zip = ZipArchive::new(Cursor::new(&packed[..])).unwrap();
+        let mut names: Vec<&str> = zip.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            vec!["/assets.json", "/images/bird.png", "/sounds/sfx/jump.wav"]
+        );
+        for (path, bytes) in FILES.iter() {
+            let read = read_packed(&packed, &format!("/{}", path)).unwrap();
+            assert_eq!(read, *bytes, "{}", path);
+        }
+        assert!(read_packed(&packed, "/images/missing.png").is_err());
+    }
+}
@@ -0,0 +1,19 @@
+use std::env;
+use std::process;
+
+/// Packs a resources directory into a zip archive, to ship next to a game
+/// as `resources.zip`:
+///
+/// `cargo run -p pack-resources -- flappy-bird/resources target/release/resources.zip`
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("Usage: {} <resources dir> <archive.zip>", args[0]);
+        process::exit(2);
+    }
+
+    if let Err(e) = pack_resources::pack(&args[1], &args[2]) {
+        eprintln!("Unable to pack {} into {}: {}", args[1], args[2], e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    pack_resources::build_script();
+}
@@ -1,13 +1,10 @@
-use specs::{Component, VecStorage, World, WorldExt};
+use ggez_extras::{particles, render};
+use specs::{Component, Entity, VecStorage, World, WorldExt};
 
 use crate::types::Side;
 
-#[derive(Clone, Debug, Component, Default)]
-#[storage(VecStorage)]
-pub struct Position {
-    pub x: f32,
-    pub y: f32,
-}
+pub use particles::ParticleEmitter;
+pub use render::{Position, Render, Shape, Size, Text};
 
 #[derive(Clone, Debug, Component, Default)]
 #[storage(VecStorage)]
@@ -16,13 +13,6 @@ pub struct Velocity {
     pub y: f32,
 }
 
-#[derive(Clone, Debug, Component, Default)]
-#[storage(VecStorage)]
-pub struct Size {
-    pub w: f32,
-    pub h: f32,
-}
-
 #[derive(Clone, Debug, Component)]
 #[storage(VecStorage)]
 pub struct Player {
@@ -47,13 +37,18 @@ pub struct Won(pub bool);
 #[storage(VecStorage)]
 pub struct Serving(pub bool);
 
+/// Marks a text entity that displays the score of the given player.
+#[derive(Clone, Debug, Component)]
+#[storage(VecStorage)]
+pub struct ScoreText(pub Entity);
+
 pub fn register(world: &mut World) {
-    world.register::<Position>();
+    render::register(world);
     world.register::<Velocity>();
-    world.register::<Size>();
     world.register::<Player>();
     world.register::<Ball>();
     world.register::<Serving>();
     world.register::<Scored>();
     world.register::<Won>();
+    world.register::<ScoreText>();
 }
@@ -6,10 +6,15 @@ use ggez_extras::input;
 pub enum Button {
     Enter,
     Quit,
+    Debug,
+    Mute,
     LeftPlayerUp,
     LeftPlayerDown,
     RightPlayerUp,
     RightPlayerDown,
+    Left,
+    Right,
+    Settings,
 }
 
 pub type Binding = input::InputBinding<Button>;
@@ -22,6 +27,11 @@ pub fn create_input_binding() -> input::InputBinding<Button> {
         .bind_key_to_button(KeyCode::S, Button::LeftPlayerDown)
         .bind_key_to_button(KeyCode::Up, Button::RightPlayerUp)
         .bind_key_to_button(KeyCode::Down, Button::RightPlayerDown)
+        .bind_key_to_button(KeyCode::Left, Button::Left)
+        .bind_key_to_button(KeyCode::Right, Button::Right)
         .bind_key_to_button(KeyCode::Return, Button::Enter)
         .bind_key_to_button(KeyCode::Escape, Button::Quit)
+        .bind_key_to_button(KeyCode::F2, Button::Settings)
+        .bind_key_to_button(KeyCode::F3, Button::Debug)
+        .bind_key_to_button(KeyCode::M, Button::Mute)
 }
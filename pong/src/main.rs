@@ -1,13 +1,18 @@
-use std::env;
 use std::path;
 
-use ggez::{
-    audio, audio::SoundSource, conf, event, graphics, mint, timer, Context, ContextBuilder,
-    GameResult,
+use ggez::{conf, event, graphics, timer, Context, ContextBuilder, GameError, GameResult};
+use ggez_extras::{
+    assets::{AssetWatcher, Assets},
+    audio::{AudioManager, AudioSystem, Bus, SoundQueue},
+    camera::{Camera, CameraSystem},
+    logging,
+    particles::ParticleSystem,
+    render::{DebugOverlay, RenderLayers, RenderSystem, ScaledCanvas, ScalingMode},
+    resources,
+    settings::Settings,
 };
-use ggez_extras::{logging, util};
-use log::info;
-use specs::{Builder, Join, World, WorldExt};
+use log::{info, warn};
+use specs::{Builder, Join, RunNow, World, WorldExt};
 
 mod components;
 mod input;
@@ -17,7 +22,19 @@ mod types;
 
 use types::*;
 
+// With the `embed-resources` feature, the build script packs the resources
+// into the binary.
+#[cfg(feature = "embed-resources")]
+const PACKED_RESOURCES: Option<&[u8]> =
+    Some(include_bytes!(concat!(env!("OUT_DIR"), "/resources.zip")));
+#[cfg(not(feature = "embed-resources"))]
+const PACKED_RESOURCES: Option<&[u8]> = None;
+
+const GAME_ID: &str = "pong";
+const AUTHOR: &str = "Tommy Maintz";
+
 const DESIRED_UPS: u32 = 60;
+// The window size until the player picks another one in the settings.
 const WINDOW_WIDTH: f32 = 1280.0;
 const WINDOW_HEIGHT: f32 = 780.0;
 
@@ -26,14 +43,28 @@ const VIRTUAL_HEIGHT: f32 = 243.0;
 
 const PADDLE_SPEED: f32 = 160.0;
 
+const ASSETS_PATH: &str = "/assets.json";
+
+const LAYER_GAME: &str = "game";
+const LAYER_UI: &str = "ui";
+
 pub struct Game {
     world: World,
     scenes: scenes::SceneStack,
     input_binding: input::Binding,
+    /// Owned by the game rather than the world, as the game drives it.
+    render_system: RenderSystem,
+    /// Reloads changed assets in debug builds.
+    watcher: Option<AssetWatcher>,
 }
 
 impl Game {
-    fn new(ctx: &mut Context, _resource_path: &path::Path) -> GameResult<Game> {
+    fn new(
+        ctx: &mut Context,
+        resource_path: &path::Path,
+        settings: Settings,
+        audio: bool,
+    ) -> GameResult<Game> {
         let mut world = World::new();
         components::register(&mut world);
 
@@ -42,131 +73,205 @@ impl Game {
             delta: 1.0 / DESIRED_UPS as f32,
         });
         world.insert(input::State::new());
-        world.insert(Fonts {
-            retro: graphics::Font::new(ctx, "/fonts/font.ttf")?,
-        });
-        world.insert(Sounds {
-            paddle_hit: audio::Source::new(ctx, "/sounds/paddle_hit.wav")?,
-            score: audio::Source::new(ctx, "/sounds/score.wav")?,
-            wall_hit: audio::Source::new(ctx, "/sounds/wall_hit.wav")?,
-        });
-
-        world
-            .create_entity()
-            .with(components::Player {
-                name: String::from("1"),
-                side: Side::Left,
-                score: 0,
-            })
-            .with(components::Position { x: 10.0, y: 30.0 })
-            .with(components::Size { w: 5.0, h: 20.0 })
-            .with(components::Scored(false))
-            .with(components::Won(false))
-            .with(components::Serving(true))
-            .build();
+        world.insert(Camera::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT));
+        world.insert(RenderLayers::new().world(LAYER_GAME, 1.0).screen(LAYER_UI));
 
-        world
-            .create_entity()
-            .with(components::Player {
-                name: String::from("2"),
-                side: Side::Right,
-                score: 0,
-            })
-            .with(components::Position {
-                x: VIRTUAL_WIDTH - 10.0,
-                y: VIRTUAL_HEIGHT - 50.0,
-            })
-            .with(components::Size { w: 5.0, h: 20.0 })
-            .with(components::Scored(false))
-            .with(components::Won(false))
-            .with(components::Serving(false))
-            .build();
+        let render_system = RenderSystem::new(&mut world)
+            .with_show_fps(settings.show_fps)
+            .with_canvas(ScaledCanvas::new(
+                ctx,
+                VIRTUAL_WIDTH as u16,
+                VIRTUAL_HEIGHT as u16,
+                ScalingMode::Letterbox,
+            )?);
+        world.insert(
+            DebugOverlay::new().with_vectors(|velocity: &components::Velocity| {
+                ggez::mint::Vector2 {
+                    x: velocity.x * 0.25,
+                    y: velocity.y * 0.25,
+                }
+            }),
+        );
 
-        world
-            .create_entity()
-            .with(components::Ball {})
-            .with(components::Position {
-                x: VIRTUAL_WIDTH / 2.0 - 2.0,
-                y: VIRTUAL_HEIGHT / 2.0 - 2.0,
-            })
-            .with(components::Size { w: 4.0, h: 4.0 })
-            .with(components::Velocity::default())
-            .build();
+        // The loading scene fills the assets and then calls `setup_assets`.
+        world.insert(AudioManager::new(audio).with_settings(settings.audio.clone()));
+        world.insert(settings);
+        world.insert(Assets::new());
+        world.insert(SoundQueue::new());
 
         let mut scenestack = scenes::SceneStack::new(ctx);
-        let start_scene = Box::new(scenes::StartScene::new(ctx, &mut world));
-        scenestack.push(start_scene);
+        let loading_scene = Box::new(scenes::LoadingScene::new(ctx));
+        scenestack.push(loading_scene, &mut world);
 
         Ok(Self {
             world,
             scenes: scenestack,
             input_binding: input::create_input_binding(),
+            render_system,
+            watcher: if cfg!(debug_assertions) {
+                Some(AssetWatcher::new(resource_path))
+            } else {
+                None
+            },
         })
     }
 
-    fn draw_fps(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let font_resource = &self.world.read_resource::<Fonts>();
-
-        let fps = timer::fps(ctx);
-        let fps_display =
-            graphics::Text::new((format!("FPS: {:.1}", fps), font_resource.retro, 8.0));
-
-        graphics::queue_text(
-            ctx,
-            &fps_display,
-            mint::Point2 { x: 10.0, y: 10.0 },
-            Some(graphics::Color::from_rgb(0, 255, 0)),
-        );
-
+    /// Picks up the assets the watcher reloaded for the entities that are
+    /// created from now on.
+    fn reload_assets(&mut self, ctx: &mut Context) -> GameResult {
+        let watcher = match &mut self.watcher {
+            Some(watcher) => watcher,
+            None => return Ok(()),
+        };
+        // Until the loading scene is done, there is nothing to update.
+        if watcher.update(ctx, &self.world) && self.world.has_value::<Fonts>() {
+            let fonts = Fonts::load(&self.world.read_resource::<Assets>())?;
+            *self.world.write_resource::<Fonts>() = fonts;
+        }
         Ok(())
     }
 
-    fn draw_rects(&mut self, ctx: &mut Context) -> GameResult<()> {
-        for (pos, size) in (
-            &self.world.read_storage::<components::Position>(),
-            &self.world.read_storage::<components::Size>(),
+    fn update_scores(&mut self) {
+        let players = self.world.read_storage::<components::Player>();
+        let mut texts = self.world.write_storage::<components::Text>();
+        for (entity, score_text) in (
+            &self.world.entities(),
+            &self.world.read_storage::<components::ScoreText>(),
         )
             .join()
         {
-            let rect = graphics::Rect::new(pos.x, pos.y, size.w, size.h);
-            let draw_rect = graphics::Mesh::new_rectangle(
-                ctx,
-                graphics::DrawMode::fill(),
-                rect,
-                graphics::WHITE,
-            )?;
-            graphics::draw(ctx, &draw_rect, graphics::DrawParam::default())?;
+            let score = match players.get(score_text.0) {
+                Some(player) => player.score.to_string(),
+                None => continue,
+            };
+            // Writing the text flags it as modified, which makes the
+            // renderer lay it out again, so only changed scores are written.
+            if texts.get(entity).is_some_and(|text| text.text != score) {
+                if let Some(text) = texts.get_mut(entity) {
+                    text.text = score;
+                }
+            }
         }
-
-        Ok(())
     }
+}
 
-    fn draw_scores(&mut self, ctx: &mut Context) -> GameResult<()> {
-        let font_resource = &self.world.read_resource::<Fonts>();
-
-        for player in (&self.world.read_storage::<components::Player>()).join() {
-            let score_display =
-                graphics::Text::new((format!("{}", player.score), font_resource.retro, 32.0));
-            let pos = match player.side {
-                Side::Left => mint::Point2 {
-                    x: VIRTUAL_WIDTH / 2.0 - 50.0,
-                    y: VIRTUAL_HEIGHT / 3.0,
-                },
-                Side::Right => mint::Point2 {
-                    x: VIRTUAL_WIDTH / 2.0 + 30.0,
-                    y: VIRTUAL_HEIGHT / 3.0,
-                },
-            };
+/// Sets up the resources and entities that use the assets, once the
+/// loading scene loaded them.
+fn setup_assets(world: &mut World) -> GameResult {
+    let (fonts, sounds) = {
+        let assets = world.read_resource::<Assets>();
+        let sounds = Sounds {
+            paddle_hit: assets.sound("paddle_hit")?,
+            score: assets.sound("score")?,
+            wall_hit: assets.sound("wall_hit")?,
+        };
+        (Fonts::load(&assets)?, sounds)
+    };
 
-            graphics::queue_text(ctx, &score_display, pos, Some(graphics::WHITE));
-        }
+    world.write_resource::<DebugOverlay>().font = fonts.retro;
+    world.insert(fonts.clone());
+    world.insert(sounds);
 
-        Ok(())
+    let left_player = world
+        .create_entity()
+        .with(components::Player {
+            name: String::from("1"),
+            side: Side::Left,
+            score: 0,
+        })
+        .with(components::Position { x: 10.0, y: 30.0 })
+        .with(components::Size { w: 5.0, h: 20.0 })
+        .with(components::Render {
+            visible: true,
+            layer: LAYER_GAME,
+        })
+        .with(components::Shape::rectangle(5.0, 20.0, graphics::WHITE))
+        .with(components::Scored(false))
+        .with(components::Won(false))
+        .with(components::Serving(true))
+        .build();
+
+    let right_player = world
+        .create_entity()
+        .with(components::Player {
+            name: String::from("2"),
+            side: Side::Right,
+            score: 0,
+        })
+        .with(components::Position {
+            x: VIRTUAL_WIDTH - 10.0,
+            y: VIRTUAL_HEIGHT - 50.0,
+        })
+        .with(components::Size { w: 5.0, h: 20.0 })
+        .with(components::Render {
+            visible: true,
+            layer: LAYER_GAME,
+        })
+        .with(components::Shape::rectangle(5.0, 20.0, graphics::WHITE))
+        .with(components::Scored(false))
+        .with(components::Won(false))
+        .with(components::Serving(false))
+        .build();
+
+    world
+        .create_entity()
+        .with(components::Ball {})
+        .with(components::Position {
+            x: VIRTUAL_WIDTH / 2.0 - 2.0,
+            y: VIRTUAL_HEIGHT / 2.0 - 2.0,
+        })
+        .with(components::Size { w: 4.0, h: 4.0 })
+        .with(components::Render {
+            visible: true,
+            layer: LAYER_GAME,
+        })
+        .with(components::Shape::rectangle(4.0, 4.0, graphics::WHITE))
+        .with(components::Velocity::default())
+        .with(
+            components::ParticleEmitter::new(0.2..0.5)
+                .with_speed(40.0..90.0)
+                .with_offset(2.0, 2.0)
+                .with_sizes(2.0, 0.5)
+                .with_colors(
+                    graphics::Color::new(1.0, 0.9, 0.4, 1.0),
+                    graphics::Color::new(1.0, 0.4, 0.1, 0.0),
+                ),
+        )
+        .build();
+
+    for (player, x) in &[
+        (left_player, VIRTUAL_WIDTH / 2.0 - 50.0),
+        (right_player, VIRTUAL_WIDTH / 2.0 + 30.0),
+    ] {
+        world
+            .create_entity()
+            .with(components::ScoreText(*player))
+            .with(components::Render {
+                visible: true,
+                layer: LAYER_GAME,
+            })
+            .with(components::Text {
+                text: String::from("0"),
+                font: fonts.retro,
+                font_size: 32.0,
+                color: graphics::WHITE,
+                align: Alignment::Left,
+                ..Default::default()
+            })
+            .with(components::Position {
+                x: *x,
+                y: VIRTUAL_HEIGHT / 3.0,
+            })
+            .build();
     }
+
+    Ok(())
 }
 
 impl event::EventHandler for Game {
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.reload_assets(ctx)?;
+
         if self
             .world
             .read_resource::<input::State>()
@@ -174,9 +279,34 @@ impl event::EventHandler for Game {
         {
             event::quit(ctx);
         }
+        if self
+            .world
+            .read_resource::<input::State>()
+            .get_button_released(input::Button::Debug)
+        {
+            self.world.write_resource::<DebugOverlay>().toggle();
+        }
+        if self
+            .world
+            .read_resource::<input::State>()
+            .get_button_released(input::Button::Mute)
+        {
+            let mut audio = self.world.write_resource::<AudioManager>();
+            audio.toggle_muted(Bus::Master);
+            let mut settings = self.world.write_resource::<Settings>();
+            settings.audio = audio.settings().clone();
+            if let Err(e) = settings.save(GAME_ID, AUTHOR) {
+                warn!("Unable to save settings: {}", e);
+            }
+        }
 
         while timer::check_update_time(ctx, DESIRED_UPS) {
             self.scenes.update(&mut self.world, ctx);
+            CameraSystem.run_now(&self.world);
+            ParticleSystem.run_now(&self.world);
+            AudioSystem.run_now(&self.world);
+            self.world.write_resource::<DebugOverlay>().end_tick();
+            self.update_scores();
             self.world.write_resource::<input::State>().update();
             self.world.maintain();
         }
@@ -185,26 +315,22 @@ impl event::EventHandler for Game {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
-        graphics::clear(ctx, graphics::Color::from_rgb(40, 45, 52));
-
-        self.scenes.draw(&self.world, ctx);
-
-        self.draw_rects(ctx)?;
-        self.draw_scores(ctx)?;
-        self.draw_fps(ctx)?;
-
-        graphics::draw_queued_text(
-            ctx,
-            graphics::DrawParam::default(),
-            None,
-            graphics::FilterMode::Nearest,
-        )?;
-
-        graphics::present(ctx)?;
+        // The fonts are there once the loading scene is done, and replaced
+        // when the watcher reloads them.
+        if let Some(fonts) = self.world.try_fetch::<Fonts>() {
+            self.render_system.set_fps_font(fonts.retro);
+        }
+        let show_fps = self.world.read_resource::<Settings>().show_fps;
+        self.render_system.set_show_fps(show_fps);
+        self.render_system.run(ctx, &self.world)?;
 
         Ok(())
     }
 
+    fn resize_event(&mut self, _ctx: &mut Context, width: f32, height: f32) {
+        self.render_system.resize(width, height);
+    }
+
     fn key_down_event(
         &mut self,
         _ctx: &mut Context,
@@ -238,25 +364,30 @@ impl event::EventHandler for Game {
 fn main() -> GameResult {
     logging::setup();
 
-    let resource_dir = if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
-        let mut path = path::PathBuf::from(manifest_dir);
-        path.push("resources");
-        path
-    } else {
-        path::PathBuf::from("./resources")
-    };
+    let resource_dir = resources::resource_dir();
     info!("Resource dir: {:?}", resource_dir);
 
-    let cb = ContextBuilder::new("pong", "Tommy Maintz")
-        .window_setup(conf::WindowSetup::default().title("Pong"))
-        .window_mode(
-            conf::WindowMode::default()
-                .dimensions(WINDOW_WIDTH, WINDOW_HEIGHT)
-                .resizable(true)
-                .borderless(false),
-        )
-        .add_resource_path(&resource_dir);
-    let (ctx, ev) = &mut cb.build().unwrap();
+    let settings = Settings::load(
+        GAME_ID,
+        AUTHOR,
+        Settings::default().with_window_size(WINDOW_WIDTH, WINDOW_HEIGHT),
+    );
+    let cb = ContextBuilder::new(GAME_ID, AUTHOR)
+        .window_setup(settings.window_setup(conf::WindowSetup::default().title("Pong")))
+        .window_mode(settings.window_mode());
+    let cb = resources::mount(cb, &resource_dir, PACKED_RESOURCES);
+    // Without an audio device the game still runs, just without sound.
+    let ((ctx, ev), audio) = &mut match cb.clone().build() {
+        Ok(context) => (context, true),
+        Err(GameError::AudioError(e)) => {
+            warn!("Unable to start with audio, continuing without: {}", e);
+            let context = cb
+                .modules(conf::ModuleConf::default().audio(false))
+                .build()?;
+            (context, false)
+        }
+        Err(e) => return Err(e),
+    };
 
     graphics::set_default_filter(ctx, graphics::FilterMode::Nearest);
     graphics::set_screen_coordinates(
@@ -269,7 +400,7 @@ fn main() -> GameResult {
         },
     )?;
 
-    let state = &mut Game::new(ctx, &resource_dir)?;
+    let state = &mut Game::new(ctx, &resource_dir, settings, *audio)?;
 
     event::run(ctx, ev, state)
 }
@@ -1,15 +1,19 @@
 #![allow(dead_code)]
 
 use ggez::{Context, GameResult};
-use specs::World;
+use specs::{Entity, World, WorldExt};
 
+pub mod loading;
 pub mod play;
 pub mod serve;
+pub mod settings;
 pub mod start;
 pub mod won;
 
+pub use loading::*;
 pub use play::*;
 pub use serve::*;
+pub use settings::*;
 pub use start::*;
 pub use won::*;
 
@@ -23,18 +27,10 @@ pub enum SceneSwitch {
 }
 
 /// A trait for you to implement on a scene.
-/// Defines the callbacks the scene uses:
-/// a common context type `C`
 pub trait Scene {
     fn update(&mut self, world: &mut World, ctx: &mut Context) -> SceneSwitch;
-    fn draw(&mut self, world: &World, ctx: &mut Context) -> GameResult<()>;
-
-    /// This returns whether or not to draw the next scene down on the
-    /// stack as well; this is useful for layers or GUI stuff that
-    /// only partially covers the screen.
-    fn draw_previous(&self) -> bool {
-        false
-    }
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>>;
+    fn on_leave(&mut self, world: &mut World) -> GameResult;
 }
 
 impl SceneSwitch {
@@ -56,11 +52,15 @@ impl SceneSwitch {
 /// A stack of `Scene`'s, together with a context object.
 pub struct SceneStack {
     scenes: Vec<Box<dyn Scene>>,
+    entities: Vec<Option<Vec<Entity>>>,
 }
 
 impl SceneStack {
     pub fn new(_ctx: &mut Context) -> Self {
-        Self { scenes: Vec::new() }
+        Self {
+            scenes: Vec::new(),
+            entities: Vec::new(),
+        }
     }
 
     pub fn is_empty(&mut self) -> bool {
@@ -68,16 +68,26 @@ impl SceneStack {
     }
 
     /// Add a new scene to the top of the stack.
-    pub fn push(&mut self, scene: Box<dyn Scene>) {
-        self.scenes.push(scene)
+    pub fn push(&mut self, mut scene: Box<dyn Scene>, world: &mut World) {
+        self.entities
+            .push(scene.on_enter(world).expect("error pushing scene"));
+        self.scenes.push(scene);
     }
 
     /// Remove the top scene from the stack and returns it;
     /// panics if there is none.
-    pub fn pop(&mut self) -> Box<dyn Scene> {
-        self.scenes
+    pub fn pop(&mut self, world: &mut World) -> Box<dyn Scene> {
+        let mut scene = self
+            .scenes
             .pop()
-            .expect("ERROR: Popped an empty scene stack.")
+            .expect("ERROR: Popped an empty scene stack.");
+        scene.on_leave(world).expect("error popping scene");
+
+        if let Some(Some(scene_entities)) = self.entities.pop() {
+            let _ = world.delete_entities(scene_entities.as_slice());
+        }
+
+        scene
     }
 
     /// Returns the current scene; panics if there is none.
@@ -90,20 +100,20 @@ impl SceneStack {
 
     /// Executes the given SceneSwitch command; if it is a pop or replace
     /// it returns `Some(old_scene)`, otherwise `None`
-    pub fn switch(&mut self, next_scene: SceneSwitch) -> Option<Box<dyn Scene>> {
+    pub fn switch(&mut self, next_scene: SceneSwitch, world: &mut World) -> Option<Box<dyn Scene>> {
         match next_scene {
             SceneSwitch::None => None,
             SceneSwitch::Pop => {
-                let s = self.pop();
+                let s = self.pop(world);
                 Some(s)
             }
             SceneSwitch::Push(s) => {
-                self.push(s);
+                self.push(s, world);
                 None
             }
             SceneSwitch::Replace(s) => {
-                let old_scene = self.pop();
-                self.push(s);
+                let old_scene = self.pop(world);
+                self.push(s, world);
                 Some(old_scene)
             }
         }
@@ -120,27 +130,6 @@ impl SceneStack {
                 .expect("Tried to update empty scene stack");
             current_scene.update(world, ctx)
         };
-        self.switch(next_scene);
-    }
-
-    /// We walk down the scene stack until we find a scene where we aren't
-    /// supposed to draw the previous one, then draw them from the bottom up.
-    ///
-    /// This allows for layering GUI's and such.
-    fn draw_scenes(scenes: &mut [Box<dyn Scene>], world: &World, ctx: &mut Context) {
-        assert!(scenes.len() > 0);
-        if let Some((current, rest)) = scenes.split_last_mut() {
-            if current.draw_previous() {
-                SceneStack::draw_scenes(rest, world, ctx);
-            }
-            current
-                .draw(world, ctx)
-                .expect("I would hope drawing a scene never fails!");
-        }
-    }
-
-    /// Draw the current scene.
-    pub fn draw(&mut self, world: &World, ctx: &mut Context) {
-        SceneStack::draw_scenes(&mut self.scenes, world, ctx)
+        self.switch(next_scene, world);
     }
 }
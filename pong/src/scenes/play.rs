@@ -1,5 +1,5 @@
 use ggez::{Context, GameResult};
-use specs::{Join, World};
+use specs::{Entity, Join, World};
 
 use crate::*;
 
@@ -56,7 +56,11 @@ impl super::Scene for PlayScene {
         }
     }
 
-    fn draw(&mut self, _world: &World, _ctx: &mut Context) -> GameResult<()> {
+    fn on_enter(&mut self, _world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        Ok(None)
+    }
+
+    fn on_leave(&mut self, _world: &mut World) -> GameResult {
         Ok(())
     }
 }
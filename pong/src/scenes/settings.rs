@@ -0,0 +1,114 @@
+use ggez::{graphics, Context, GameResult};
+use ggez_extras::settings::{Settings, SettingsMenu};
+use log::warn;
+use specs::{Builder, Entity, World, WorldExt};
+
+use crate::*;
+
+/// Lists the settings; Up and Down select one, Left and Right change it.
+pub struct SettingsScene {
+    menu: Option<SettingsMenu>,
+}
+
+impl SettingsScene {
+    pub fn new(_ctx: &mut Context, _world: &mut World) -> Self {
+        Self { menu: None }
+    }
+}
+
+impl super::Scene for SettingsScene {
+    fn update(&mut self, world: &mut World, ctx: &mut Context) -> scenes::SceneSwitch {
+        let (close, up, down, step) = {
+            let input = world.read_resource::<input::State>();
+            let step = if input.get_button_pressed(input::Button::Left) {
+                -1
+            } else if input.get_button_pressed(input::Button::Right) {
+                1
+            } else {
+                0
+            };
+            (
+                input.get_button_released(input::Button::Settings),
+                input.get_button_pressed(input::Button::RightPlayerUp),
+                input.get_button_pressed(input::Button::RightPlayerDown),
+                step,
+            )
+        };
+        if close {
+            return scenes::SceneSwitch::replace(scenes::StartScene::new(ctx, world));
+        }
+
+        if let Some(menu) = &mut self.menu {
+            if up {
+                menu.select_previous(world);
+            }
+            if down {
+                menu.select_next(world);
+            }
+            if step != 0 {
+                if let Err(e) = menu.change(world, ctx, step) {
+                    warn!("Unable to change the setting: {}", e);
+                }
+            }
+        }
+        scenes::SceneSwitch::None
+    }
+
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        let font = world.read_resource::<Fonts>().retro;
+        let mut entities = vec![
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: String::from("Settings"),
+                    font,
+                    font_size: 10.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position { x: 0.0, y: 10.0 })
+                .build(),
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: String::from("Arrows to change, F2 to go back"),
+                    font,
+                    font_size: 8.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position {
+                    x: 0.0,
+                    y: VIRTUAL_HEIGHT - 20.0,
+                })
+                .build(),
+        ];
+
+        let mut menu = SettingsMenu::new(LAYER_UI, font, 8.0, VIRTUAL_WIDTH);
+        entities.extend(menu.create_entities(world, 60.0));
+        self.menu = Some(menu);
+        Ok(Some(entities))
+    }
+
+    fn on_leave(&mut self, world: &mut World) -> GameResult {
+        world.read_resource::<Settings>().save(GAME_ID, AUTHOR)
+    }
+}
@@ -1,5 +1,5 @@
 use ggez::{graphics, Context, GameResult};
-use specs::World;
+use specs::{Builder, Entity, World, WorldExt};
 
 use crate::*;
 
@@ -18,34 +18,63 @@ impl super::Scene for StartScene {
             .get_button_released(input::Button::Enter)
         {
             scenes::SceneSwitch::replace(scenes::ServeScene::new(ctx, world))
+        } else if world
+            .read_resource::<input::State>()
+            .get_button_released(input::Button::Settings)
+        {
+            scenes::SceneSwitch::replace(scenes::SettingsScene::new(ctx, world))
         } else {
             scenes::SceneSwitch::None
         }
     }
 
-    fn draw(&mut self, world: &World, ctx: &mut Context) -> GameResult<()> {
-        let font_resource = &world.read_resource::<Fonts>();
-
-        let t1 = graphics::Text::new(("Welcome to Pong!", font_resource.retro, 10.0));
-        let t2 = graphics::Text::new(("Press Enter to begin!", font_resource.retro, 10.0));
-
-        let t1_x = (VIRTUAL_WIDTH / 2.0) - (t1.dimensions(ctx).0 / 2) as f32;
-        let t2_x = (VIRTUAL_WIDTH / 2.0) - (t2.dimensions(ctx).0 / 2) as f32;
-
-        graphics::queue_text(
-            ctx,
-            &t1,
-            mint::Point2 { x: t1_x, y: 10.0 },
-            Some(graphics::WHITE),
-        );
-
-        graphics::queue_text(
-            ctx,
-            &t2,
-            mint::Point2 { x: t2_x, y: 20.0 },
-            Some(graphics::WHITE),
-        );
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        let font = world.read_resource::<Fonts>().retro;
+        Ok(Some(vec![
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: String::from("Welcome to Pong!"),
+                    font,
+                    font_size: 10.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position { x: 0.0, y: 10.0 })
+                .build(),
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: String::from("Press Enter to begin, F2 for settings"),
+                    font,
+                    font_size: 10.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position { x: 0.0, y: 20.0 })
+                .build(),
+        ]))
+    }
 
+    fn on_leave(&mut self, _world: &mut World) -> GameResult {
         Ok(())
     }
 }
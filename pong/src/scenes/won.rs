@@ -1,5 +1,5 @@
 use ggez::{graphics, Context, GameResult};
-use specs::{Join, World};
+use specs::{Builder, Entity, Join, World, WorldExt};
 
 use crate::*;
 
@@ -37,8 +37,10 @@ impl super::Scene for WonScene {
         }
     }
 
-    fn draw(&mut self, world: &World, ctx: &mut Context) -> GameResult<()> {
-        let font_resource = &world.read_resource::<Fonts>();
+    fn on_enter(&mut self, world: &mut World) -> GameResult<Option<Vec<Entity>>> {
+        let font = world.read_resource::<Fonts>().retro;
+
+        let mut winner_name = String::new();
         for (player, won) in (
             &world.read_storage::<components::Player>(),
             &world.read_storage::<components::Won>(),
@@ -46,33 +48,55 @@ impl super::Scene for WonScene {
             .join()
         {
             if won.0 {
-                let t1 = graphics::Text::new((
-                    format!("Player {} wins!", player.name),
-                    font_resource.retro,
-                    28.0,
-                ));
-                let t2 =
-                    graphics::Text::new(("Press Enter to restart!", font_resource.retro, 16.0));
-
-                let t1_x = (VIRTUAL_WIDTH / 2.0) - (t1.dimensions(ctx).0 / 2) as f32;
-                let t2_x = (VIRTUAL_WIDTH / 2.0) - (t2.dimensions(ctx).0 / 2) as f32;
-
-                graphics::queue_text(
-                    ctx,
-                    &t1,
-                    mint::Point2 { x: t1_x, y: 10.0 },
-                    Some(graphics::WHITE),
-                );
-
-                graphics::queue_text(
-                    ctx,
-                    &t2,
-                    mint::Point2 { x: t2_x, y: 40.0 },
-                    Some(graphics::WHITE),
-                );
+                winner_name = player.name.clone();
             }
         }
 
+        Ok(Some(vec![
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: format!("Player {} wins!", winner_name),
+                    font,
+                    font_size: 28.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position { x: 0.0, y: 10.0 })
+                .build(),
+            world
+                .create_entity()
+                .with(components::Render {
+                    visible: true,
+                    layer: LAYER_UI,
+                })
+                .with(components::Text {
+                    text: String::from("Press Enter to restart!"),
+                    font,
+                    font_size: 16.0,
+                    color: graphics::WHITE,
+                    align: Alignment::Centered,
+                    ..Default::default()
+                })
+                .with(components::Size {
+                    w: VIRTUAL_WIDTH,
+                    h: f32::INFINITY,
+                })
+                .with(components::Position { x: 0.0, y: 40.0 })
+                .build(),
+        ]))
+    }
+
+    fn on_leave(&mut self, _world: &mut World) -> GameResult {
         Ok(())
     }
 }
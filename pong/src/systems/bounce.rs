@@ -1,31 +1,52 @@
 use ggez::graphics::Rect;
+use ggez_extras::audio::{pan_from_position, PlayParams, SoundQueue};
+use ggez_extras::render::DebugOverlay;
 use ggez_extras::util::collides;
 use rand::Rng;
-use specs::{Join, ReadStorage, System, WriteExpect, WriteStorage};
+use specs::{Join, ReadExpect, ReadStorage, System, Write, WriteStorage};
 
 use crate::*;
 
 pub struct BounceSystem;
 impl<'s> System<'s> for BounceSystem {
     type SystemData = (
-        WriteExpect<'s, Sounds>,
+        ReadExpect<'s, Sounds>,
+        Write<'s, SoundQueue>,
+        Option<Write<'s, DebugOverlay>>,
         ReadStorage<'s, components::Ball>,
         ReadStorage<'s, components::Player>,
         WriteStorage<'s, components::Position>,
         WriteStorage<'s, components::Velocity>,
         ReadStorage<'s, components::Size>,
+        WriteStorage<'s, components::ParticleEmitter>,
     );
 
     fn run(
         &mut self,
-        (mut sounds, balls, players, mut positions, mut velocities, sizes): Self::SystemData,
+        (
+            sounds,
+            mut sound_queue,
+            mut debug,
+            balls,
+            players,
+            mut positions,
+            mut velocities,
+            sizes,
+            mut emitters,
+        ): Self::SystemData,
     ) {
         let mut rng = rand::thread_rng();
         let mut new_ball_x: f32 = 0.0;
         let mut new_ball_y: f32 = 0.0;
 
-        for (_, ball_pos, ball_size, ball_vel) in
-            (&balls, &positions, &sizes, &mut velocities).join()
+        for (_, ball_pos, ball_size, ball_vel, mut ball_emitter) in (
+            &balls,
+            &positions,
+            &sizes,
+            &mut velocities,
+            (&mut emitters).maybe(),
+        )
+            .join()
         {
             new_ball_x = ball_pos.x;
             new_ball_y = ball_pos.y;
@@ -34,20 +55,25 @@ impl<'s> System<'s> for BounceSystem {
             if ball_pos.y <= 0.0 {
                 new_ball_y = 0.0;
                 ball_vel.y = -ball_vel.y;
-                let _ = sounds.wall_hit.play();
+                sound_queue.play_with(sounds.wall_hit, hit_params(&mut rng, ball_pos));
             }
             // Bounce from bottom
             else if ball_pos.y >= VIRTUAL_HEIGHT - ball_size.h {
                 new_ball_y = VIRTUAL_HEIGHT - ball_size.h;
                 ball_vel.y = -ball_vel.y;
-                let _ = sounds.wall_hit.play();
+                sound_queue.play_with(sounds.wall_hit, hit_params(&mut rng, ball_pos));
             }
 
             for (player, player_pos, player_size) in (&players, &positions, &sizes).join() {
+                let ball_rect = Rect::new(ball_pos.x, ball_pos.y, ball_size.w, ball_size.h);
                 if collides(
-                    Rect::new(ball_pos.x, ball_pos.y, ball_size.w, ball_size.h),
+                    ball_rect,
                     Rect::new(player_pos.x, player_pos.y, player_size.w, player_size.h),
                 ) {
+                    if let Some(debug) = &mut debug {
+                        debug.add_contact(ball_rect);
+                    }
+
                     match player.side {
                         // Bounce from left paddle
                         Side::Left => {
@@ -71,7 +97,10 @@ impl<'s> System<'s> for BounceSystem {
                             rng.gen_range(50.0, 100.0) * (player_pos.y + player_size.h) / ball_pos.y
                     }
 
-                    let _ = sounds.paddle_hit.play();
+                    if let Some(emitter) = &mut ball_emitter {
+                        emitter.burst(12);
+                    }
+                    sound_queue.play_with(sounds.paddle_hit, hit_params(&mut rng, ball_pos));
                 }
             }
         }
@@ -82,3 +111,74 @@ impl<'s> System<'s> for BounceSystem {
         }
     }
 }
+
+/// Makes a hit sound from where the ball is, slightly varied in pitch so
+/// hits don't get repetitive.
+fn hit_params<R: Rng>(rng: &mut R, pos: &components::Position) -> PlayParams {
+    PlayParams::new()
+        .with_pan(pan_from_position(pos, VIRTUAL_WIDTH))
+        .with_pitch(rng.gen_range(0.9, 1.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use specs::{Builder, RunNow, World, WorldExt};
+
+    use super::*;
+    use crate::systems::testing::{create_world, queued_sounds};
+
+    fn create_ball(world: &mut World, x: f32, y: f32) {
+        world
+            .create_entity()
+            .with(components::Ball {})
+            .with(components::Position { x, y })
+            .with(components::Size { w: 4.0, h: 4.0 })
+            .with(components::Velocity {
+                x: -100.0,
+                y: -50.0,
+            })
+            .build();
+    }
+
+    #[test]
+    fn queues_a_wall_hit_when_the_ball_bounces_from_a_wall() {
+        let mut world = create_world();
+        create_ball(&mut world, 100.0, -1.0);
+
+        BounceSystem.run_now(&world);
+
+        let wall_hit = world.read_resource::<Sounds>().wall_hit;
+        assert_eq!(queued_sounds(&world), vec![wall_hit]);
+    }
+
+    #[test]
+    fn queues_a_paddle_hit_when_the_ball_hits_a_paddle() {
+        let mut world = create_world();
+        create_ball(&mut world, 8.0, 50.0);
+        world
+            .create_entity()
+            .with(components::Player {
+                side: Side::Left,
+                name: String::from("Player 1"),
+                score: 0,
+            })
+            .with(components::Position { x: 5.0, y: 40.0 })
+            .with(components::Size { w: 5.0, h: 20.0 })
+            .build();
+
+        BounceSystem.run_now(&world);
+
+        let paddle_hit = world.read_resource::<Sounds>().paddle_hit;
+        assert_eq!(queued_sounds(&world), vec![paddle_hit]);
+    }
+
+    #[test]
+    fn queues_nothing_while_the_ball_flies() {
+        let mut world = create_world();
+        create_ball(&mut world, 100.0, 100.0);
+
+        BounceSystem.run_now(&world);
+
+        assert!(queued_sounds(&world).is_empty());
+    }
+}
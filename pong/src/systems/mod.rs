@@ -2,6 +2,8 @@ pub mod ball;
 pub mod bounce;
 pub mod paddle;
 pub mod score;
+#[cfg(test)]
+mod testing;
 
 pub use ball::*;
 pub use bounce::*;
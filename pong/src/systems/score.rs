@@ -1,11 +1,15 @@
-use specs::{Join, ReadStorage, System, WriteExpect, WriteStorage};
+use ggez_extras::audio::SoundQueue;
+use ggez_extras::camera::Camera;
+use specs::{Join, ReadExpect, ReadStorage, System, Write, WriteStorage};
 
 use crate::*;
 
 pub struct ScoreSystem;
 impl<'s> System<'s> for ScoreSystem {
     type SystemData = (
-        WriteExpect<'s, Sounds>,
+        ReadExpect<'s, Sounds>,
+        Write<'s, SoundQueue>,
+        Option<Write<'s, Camera>>,
         ReadStorage<'s, components::Ball>,
         WriteStorage<'s, components::Player>,
         ReadStorage<'s, components::Position>,
@@ -17,7 +21,7 @@ impl<'s> System<'s> for ScoreSystem {
 
     fn run(
         &mut self,
-        (mut sounds, balls, mut players, positions, sizes, mut serving, mut scored, mut won): Self::SystemData,
+        (sounds, mut sound_queue, mut camera, balls, mut players, positions, sizes, mut serving, mut scored, mut won): Self::SystemData,
     ) {
         for (_, ball_pos, ball_size) in (&balls, &positions, &sizes).join() {
             if ball_pos.x < 0.0 {
@@ -36,7 +40,10 @@ impl<'s> System<'s> for ScoreSystem {
                     }
                 }
 
-                let _ = sounds.score.play();
+                if let Some(camera) = &mut camera {
+                    camera.add_trauma(0.5);
+                }
+                sound_queue.play(sounds.score);
             }
 
             if ball_pos.x + ball_size.w > VIRTUAL_WIDTH {
@@ -55,8 +62,69 @@ impl<'s> System<'s> for ScoreSystem {
                     }
                 }
 
-                let _ = sounds.score.play();
+                if let Some(camera) = &mut camera {
+                    camera.add_trauma(0.5);
+                }
+                sound_queue.play(sounds.score);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use specs::{Builder, Entity, RunNow, World, WorldExt};
+
+    use super::*;
+    use crate::systems::testing::{create_world, queued_sounds};
+
+    fn create_player(world: &mut World, side: Side) -> Entity {
+        world
+            .create_entity()
+            .with(components::Player {
+                side,
+                name: String::from("Player"),
+                score: 0,
+            })
+            .with(components::Serving(false))
+            .with(components::Scored(false))
+            .with(components::Won(false))
+            .build()
+    }
+
+    fn create_ball(world: &mut World, x: f32) {
+        world
+            .create_entity()
+            .with(components::Ball {})
+            .with(components::Position { x, y: 100.0 })
+            .with(components::Size { w: 4.0, h: 4.0 })
+            .build();
+    }
+
+    #[test]
+    fn queues_the_score_sound_when_the_ball_leaves_the_field() {
+        let mut world = create_world();
+        let left = create_player(&mut world, Side::Left);
+        let right = create_player(&mut world, Side::Right);
+        create_ball(&mut world, -5.0);
+
+        ScoreSystem.run_now(&world);
+
+        let score = world.read_resource::<Sounds>().score;
+        assert_eq!(queued_sounds(&world), vec![score]);
+        assert_eq!(world.read_storage::<components::Player>().get(right).unwrap().score, 1);
+        assert!(world.read_storage::<components::Serving>().get(left).unwrap().0);
+    }
+
+    #[test]
+    fn queues_nothing_while_the_ball_is_in_the_field() {
+        let mut world = create_world();
+        create_player(&mut world, Side::Left);
+        create_player(&mut world, Side::Right);
+        create_ball(&mut world, VIRTUAL_WIDTH / 2.0);
+
+        ScoreSystem.run_now(&world);
+
+        assert!(queued_sounds(&world).is_empty());
+    }
+}
@@ -0,0 +1,29 @@
+//! What the tests of the systems share: a world with the sounds of the
+//! game, and the sounds the systems queue in it.
+use ggez_extras::audio::{Bus, Sound, SoundQueue};
+use specs::{World, WorldExt};
+
+use crate::*;
+
+/// Creates a world with the components, and sounds that play nothing.
+pub fn create_world() -> World {
+    let mut world = World::new();
+    components::register(&mut world);
+    world.insert(Sounds {
+        paddle_hit: Sound::unloaded(0, Bus::Sfx),
+        score: Sound::unloaded(1, Bus::Sfx),
+        wall_hit: Sound::unloaded(2, Bus::Sfx),
+    });
+    world.insert(SoundQueue::new());
+    world
+}
+
+/// Returns the sounds the systems queued, in order.
+pub fn queued_sounds(world: &World) -> Vec<Sound> {
+    world
+        .read_resource::<SoundQueue>()
+        .events()
+        .iter()
+        .map(|event| event.sound)
+        .collect()
+}
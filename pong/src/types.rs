@@ -1,6 +1,11 @@
-use ggez::{audio, graphics};
+use ggez::{graphics, GameResult};
+use ggez_extras::assets::Assets;
+use ggez_extras::audio::Sound;
 use specs::Entity;
 
+pub use ggez_extras::render::Alignment;
+pub use ggez_extras::types::DeltaTime;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Side {
     Left,
@@ -13,19 +18,22 @@ pub struct ServingPlayer(pub Option<Entity>);
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct WinningPlayer(pub Option<Entity>);
 
-#[derive(Clone, Debug, Default)]
-pub struct DeltaTime {
-    pub delta: f32,
-}
-
 #[derive(Clone, Debug, Default)]
 pub struct Fonts {
     pub retro: graphics::Font,
 }
 
-#[derive(Debug)]
+impl Fonts {
+    pub fn load(assets: &Assets) -> GameResult<Self> {
+        Ok(Self {
+            retro: assets.font("retro")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Sounds {
-    pub paddle_hit: audio::Source,
-    pub score: audio::Source,
-    pub wall_hit: audio::Source,
+    pub paddle_hit: Sound,
+    pub score: Sound,
+    pub wall_hit: Sound,
 }